@@ -0,0 +1,419 @@
+use crate::converter;
+use crate::diagnostics;
+use crate::preferences;
+
+const LOG_TARGET: &'static str = "iced_gif_maker::cli";
+
+pub const EXIT_CODE_SUCCESS: i32 = 0;
+pub const EXIT_CODE_USAGE: i32 = 2;
+pub const EXIT_CODE_CANCELLED: i32 = 3;
+pub const EXIT_CODE_EMPTY_STDOUT: i32 = 4;
+pub const EXIT_CODE_SPAWN: i32 = 5;
+pub const EXIT_CODE_IO: i32 = 6;
+pub const EXIT_CODE_FFMPEG: i32 = 7;
+pub const EXIT_CODE_WRITE: i32 = 8;
+pub const EXIT_CODE_NO_RESULT: i32 = 9;
+
+pub const USAGE: &'static str = "\
+Usage: iced_gif_maker [OPTIONS]
+
+Without '--output', the application window is opened (pre-loaded with the
+'--input' video, if any). With '--output', the video is converted without
+opening a window.
+
+Options:
+  -i, --input <PATH>      Video file (or directory of PNG/JPEG frames) to convert
+  -o, --output <PATH>     Where to write the result (enables headless mode)
+  -w, --width <PIXELS>    Output width (default: 480)
+  -r, --fps <FPS>         Output frame rate, up to 50 (default: 10)
+  -d, --delay <MS>        Frame delay, when converting images (default: 100)
+      --start <TIME>      Trim start, in seconds (e.g. 12.5) or as [HH:]MM:SS
+      --end <TIME>        Trim end, in seconds (e.g. 12.5) or as [HH:]MM:SS
+  -f, --format <FORMAT>   gif, webp or apng (default: from the output's extension)
+  -l, --loop <PLAYS>      Number of times the animation plays, 0 for forever (default: 0)
+  -h, --help              Print this message
+
+Exit codes:
+  0  Success
+  2  Invalid arguments
+  3  Conversion cancelled
+  4  FFmpeg did not produce any output (likely unsupported file format)
+  5  FFmpeg could not be started (or lacks required filters)
+  6  Failed to communicate with FFmpeg
+  7  FFmpeg failed
+  8  Failed to write the output file
+  9  The conversion stopped without any result";
+
+/// The command-line arguments, which are also passed to the application
+/// (as its flags) when not running headless.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub input: Option<std::path::PathBuf>,
+    pub output: Option<std::path::PathBuf>,
+    pub width: Option<u16>,
+    pub fps: Option<u8>,
+    pub frame_delay: Option<u16>,
+    pub trim_start: Option<std::time::Duration>,
+    pub trim_end: Option<std::time::Duration>,
+    pub format: Option<converter::Format>,
+    pub plays: Option<u16>,
+    pub help: bool,
+}
+
+impl Args {
+    pub fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args;
+
+        while let Some(arg) = args.next() {
+            // NOTE: Both '--name value' and '--name=value' are accepted.
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => {
+                    (name.to_string(), Some(value.to_string()))
+                }
+                _ => (arg, None),
+            };
+
+            if name == "-h" || name == "--help" {
+                parsed.help = true;
+                continue;
+            }
+
+            let value = match inline_value.or_else(|| args.next()) {
+                Some(value) => value,
+                None => return Err(format!("Missing value for '{}'.", name)),
+            };
+
+            match name.as_str() {
+                "-i" | "--input" => parsed.input = Some(value.into()),
+                "-o" | "--output" => parsed.output = Some(value.into()),
+                "-w" | "--width" => parsed.width = Some(parse_number(&name, &value)?),
+                "-r" | "--fps" => parsed.fps = Some(parse_number(&name, &value)?),
+                "-d" | "--delay" => parsed.frame_delay = Some(parse_number(&name, &value)?),
+                "--start" => parsed.trim_start = Some(parse_time(&name, &value)?),
+                "--end" => parsed.trim_end = Some(parse_time(&name, &value)?),
+                "-l" | "--loop" => parsed.plays = Some(parse_number(&name, &value)?),
+                "-f" | "--format" => {
+                    parsed.format = Some(
+                        converter::Format::from_name(&value)
+                            .ok_or(format!("Unsupported format: '{}'.", value))?,
+                    )
+                }
+                _ => return Err(format!("Unknown argument: '{}'.", name)),
+            }
+        }
+
+        if parsed.output.is_some() && parsed.input.is_none() {
+            return Err("'--output' requires '--input'.".into());
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn is_headless(&self) -> bool {
+        self.output.is_some()
+    }
+
+    /// The explicit format if any, otherwise the one matching the output's
+    /// extension, falling back to GIF.
+    fn format(&self) -> converter::Format {
+        self.format
+            .or(self
+                .output
+                .as_ref()
+                .and_then(|path| path.extension())
+                .and_then(|extension| converter::Format::from_name(&extension.to_string_lossy())))
+            .unwrap_or_default()
+    }
+
+    fn trim(&self) -> converter::Trim {
+        converter::Trim {
+            start: self.trim_start,
+            end: self.trim_end,
+        }
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for '{}': '{}'.", name, value))
+}
+
+fn parse_time(name: &str, value: &str) -> Result<std::time::Duration, String> {
+    converter::Trim::parse_time(value).ok_or(format!("Invalid value for '{}': '{}'.", name, value))
+}
+
+fn exit_code(error: &converter::Error) -> i32 {
+    match error {
+        converter::Error::Cancelled => EXIT_CODE_CANCELLED,
+        converter::Error::EmptyStdout => EXIT_CODE_EMPTY_STDOUT,
+        converter::Error::Spawn(_) => EXIT_CODE_SPAWN,
+        converter::Error::Io(_) => EXIT_CODE_IO,
+        converter::Error::Ffmpeg(_) => EXIT_CODE_FFMPEG,
+    }
+}
+
+/// Converts the video described by `args` on the current thread, printing
+/// its progress, and returns the process' exit code.
+pub fn run_headless(args: Args) -> i32 {
+    let (Some(input), Some(output)) = (args.input.as_ref(), args.output.as_ref()) else {
+        eprintln!("Both '--input' and '--output' are required.");
+        return EXIT_CODE_USAGE;
+    };
+
+    let trim = args.trim();
+    if !trim.is_valid() {
+        eprintln!("The trim start must come before the trim end.");
+        return EXIT_CODE_USAGE;
+    }
+
+    // NOTE: The binary chosen in the application's settings is used too. It
+    // is probed first, so that a missing binary (or filter) gets reported
+    // before reading any input.
+    let binary =
+        diagnostics::Binary::resolve(preferences::Preferences::load().ffmpeg_path.as_deref());
+    let report = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime.block_on(diagnostics::probe(binary)),
+        Err(e) => {
+            eprintln!("Failed to probe FFmpeg: {}", e);
+            return EXIT_CODE_SPAWN;
+        }
+    };
+    if let Some(problem) = report.problem() {
+        eprintln!("{}", problem);
+        return EXIT_CODE_SPAWN;
+    }
+
+    let source = if input.is_dir() {
+        let images = match crate::read_image_directory(input) {
+            Ok(images) if !images.is_empty() => images,
+            Ok(_) => {
+                eprintln!("No PNG or JPEG images found in {:?}.", input);
+                return EXIT_CODE_USAGE;
+            }
+            Err(e) => {
+                eprintln!("Failed to read {:?}: {}", input, e);
+                return EXIT_CODE_USAGE;
+            }
+        };
+        converter::Input::Images(converter::ImageSequence {
+            paths: images,
+            frame_delay: std::time::Duration::from_millis(
+                args.frame_delay
+                    .filter(|&delay| delay > 0)
+                    .unwrap_or(crate::DEFAULT_FRAME_DELAY_MS) as u64,
+            ),
+            frame_delays: None,
+        })
+    } else {
+        converter::Input::Video(input.to_string_lossy().to_string())
+    };
+
+    let format = args.format();
+    let settings = converter::Settings::new(
+        source,
+        args.width.unwrap_or(crate::DEFAULT_GIF_WIDTH),
+        args.fps
+            .unwrap_or(crate::DEFAULT_GIF_FPS)
+            .clamp(1, crate::MAX_GIF_FPS),
+    )
+    .trim(trim)
+    .format(format)
+    .looping(converter::Loop::from_plays(args.plays))
+    .ffmpeg_path(report.binary.path);
+
+    log::debug!(target: LOG_TARGET, "Running headless conversion: {:?}", settings);
+    println!("Converting {:?} to {} ({:?})...", input, format, output);
+
+    // NOTE: Headless conversions can only be cancelled by terminating the
+    // process, so the command sender goes unused.
+    let (converter, _command_tx, mut message_rx) = converter::Converter::new_with_channels();
+    let handle = std::thread::spawn(move || converter.convert(settings));
+
+    let code = receive_result(&mut message_rx, output);
+    if handle.join().is_err() {
+        log::warn!(target: LOG_TARGET, "Conversion thread panicked.");
+        eprintln!("The conversion stopped unexpectedly.");
+        return EXIT_CODE_NO_RESULT;
+    }
+
+    code.unwrap_or_else(|| {
+        eprintln!("The conversion stopped without any result.");
+        EXIT_CODE_NO_RESULT
+    })
+}
+
+/// Prints the conversion's progress and writes its output, until it is
+/// done. Returns the exit code, or `None` if the conversion stopped before
+/// reporting any result (e.g. its thread panicked).
+fn receive_result(
+    message_rx: &mut converter::MessageReceiver,
+    output: &std::path::Path,
+) -> Option<i32> {
+    let mut code = None;
+    let mut last_percent = None;
+    while let Some(message) = message_rx.blocking_recv() {
+        match message {
+            converter::Message::VideoDuration(duration) => {
+                println!("Video duration: {}", crate::format_duration(duration));
+            }
+            converter::Message::Progress(progress) => {
+                // NOTE: Progress is reported very often, so only print it
+                // when the displayed value changes.
+                let percent = (progress * 100.0).floor() as u8;
+                if last_percent != Some(percent) {
+                    println!("Progress: {}%", percent);
+                    last_percent = Some(percent);
+                }
+            }
+            converter::Message::Success(data) => {
+                if let Err(e) = std::fs::write(output, data) {
+                    eprintln!("Failed to write {:?}: {}", output, e);
+                    code = Some(EXIT_CODE_WRITE);
+                } else {
+                    println!("Saved {:?}", output);
+                    code = Some(EXIT_CODE_SUCCESS);
+                }
+            }
+            converter::Message::Error(e) => {
+                eprintln!("{}", e);
+                code = Some(exit_code(&e));
+            }
+            converter::Message::Done => break,
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_options() {
+        let args = parse(&[
+            "-i",
+            "in.mp4",
+            "--output=out.webp",
+            "-w",
+            "320",
+            "--fps",
+            "15",
+            "--loop",
+            "2",
+        ])
+        .unwrap();
+        assert_eq!(args.input, Some("in.mp4".into()));
+        assert_eq!(args.output, Some("out.webp".into()));
+        assert_eq!(args.width, Some(320));
+        assert_eq!(args.fps, Some(15));
+        assert_eq!(args.plays, Some(2));
+        assert!(args.is_headless());
+        assert_eq!(args.format(), converter::Format::WebP);
+        assert!(!args.help);
+    }
+
+    #[test]
+    fn parses_fractional_trim() {
+        let args = parse(&["-i", "in.mp4", "--start", "1.5", "--end=1:02.25"]).unwrap();
+        assert_eq!(
+            args.trim(),
+            converter::Trim {
+                start: Some(std::time::Duration::from_millis(1500)),
+                end: Some(std::time::Duration::from_millis(62250)),
+            }
+        );
+        assert!(parse(&["--start", "soon"]).is_err());
+    }
+
+    #[test]
+    fn explicit_format_wins() {
+        let args = parse(&["-i", "in.mp4", "-o", "out.gif", "-f", "apng"]).unwrap();
+        assert_eq!(args.format(), converter::Format::Apng);
+        let args = parse(&["-i", "in.mp4", "-o", "out"]).unwrap();
+        assert_eq!(args.format(), converter::Format::Gif);
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(parse(&["--width"]).is_err());
+        assert!(parse(&["--width", "wide"]).is_err());
+        assert!(parse(&["--bogus", "1"]).is_err());
+        assert!(parse(&["-f", "avi"]).is_err());
+        assert!(parse(&["-o", "out.gif"]).is_err());
+    }
+
+    fn receive(messages: Vec<converter::Message>, output: &std::path::Path) -> Option<i32> {
+        let (message_tx, mut message_rx) = tokio::sync::mpsc::unbounded_channel();
+        for message in messages {
+            message_tx.send(message).unwrap();
+        }
+        drop(message_tx);
+        receive_result(&mut message_rx, output)
+    }
+
+    #[test]
+    fn succeeds_only_once_the_output_is_written() {
+        let output = converter::temp_file_path("gif");
+        let code = receive(
+            vec![
+                converter::Message::Progress(0.5),
+                converter::Message::Success(vec![1, 2, 3]),
+                converter::Message::Done,
+            ],
+            &output,
+        );
+        assert_eq!(code, Some(EXIT_CODE_SUCCESS));
+        assert_eq!(std::fs::read(&output).unwrap(), [1, 2, 3]);
+        std::fs::remove_file(&output).unwrap();
+
+        let directory = std::env::temp_dir();
+        let code = receive(
+            vec![
+                converter::Message::Success(vec![1]),
+                converter::Message::Done,
+            ],
+            &directory,
+        );
+        assert_eq!(code, Some(EXIT_CODE_WRITE));
+    }
+
+    #[test]
+    fn reports_conversion_errors() {
+        let code = receive(
+            vec![
+                converter::Message::Error(converter::Error::Ffmpeg("failed".into())),
+                converter::Message::Done,
+            ],
+            &converter::temp_file_path("gif"),
+        );
+        assert_eq!(code, Some(EXIT_CODE_FFMPEG));
+    }
+
+    #[test]
+    fn fails_without_any_result() {
+        let output = converter::temp_file_path("gif");
+        assert_eq!(receive(vec![converter::Message::Done], &output), None);
+        // NOTE: The channel closes without `Done` when the thread panics.
+        assert_eq!(
+            receive(vec![converter::Message::Progress(0.5)], &output),
+            None
+        );
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn help_needs_no_value() {
+        let args = parse(&["-h"]).unwrap();
+        assert!(args.help);
+        assert!(!args.is_headless());
+    }
+}
@@ -0,0 +1,1534 @@
+// Adapted from the `ffmpeg_gif_maker` crate
+// (https://github.com/BB-301/rust-ffmpeg-gif-maker, rev 3d3fc08).
+// Copyright (c) 2023 BB-301 <fw3dg3@gmail.com>
+// Licensed under the MIT License (see the LICENSE file).
+
+use crate::overlay;
+use std::io::Read;
+
+const LOG_TARGET: &'static str = "iced_gif_maker::converter";
+
+const DEFAULT_FFMPEG_PATH: &'static str = "ffmpeg";
+const POLL_INTERVAL_MS: u64 = 50;
+const WEBP_QUALITY: u8 = 75;
+/// The shortest delay given to an image of a sequence.
+const MIN_IMAGE_DELAY: std::time::Duration = std::time::Duration::from_millis(10);
+
+static TEMP_FILE_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+pub type CommandSender = tokio::sync::mpsc::UnboundedSender<Command>;
+pub type MessageReceiver = tokio::sync::mpsc::UnboundedReceiver<Message>;
+type CommandReceiver = tokio::sync::mpsc::UnboundedReceiver<Command>;
+type MessageSender = tokio::sync::mpsc::UnboundedSender<Message>;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Command {
+    Cancel,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    VideoDuration(std::time::Duration),
+    Progress(f64),
+    Success(Vec<u8>),
+    Error(Error),
+    Done,
+}
+
+#[derive(Clone, Debug)]
+pub enum Error {
+    Cancelled,
+    EmptyStdout,
+    Spawn(String),
+    Io(String),
+    Ffmpeg(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Cancelled => write!(f, "The conversion was cancelled."),
+            Error::EmptyStdout => write!(f, "FFmpeg did not produce any output."),
+            Error::Spawn(e) => write!(f, "Failed to start FFmpeg: {}", e),
+            Error::Io(e) => write!(f, "Failed to communicate with FFmpeg: {}", e),
+            Error::Ffmpeg(e) => write!(f, "FFmpeg failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Trim {
+    pub start: Option<std::time::Duration>,
+    pub end: Option<std::time::Duration>,
+}
+
+impl Trim {
+    /// Parses a trim timestamp, given in (possibly fractional) seconds, or
+    /// as `MM:SS` or `HH:MM:SS` (e.g. `12.5`, `1:02.5` or `0:01:02.5`).
+    pub fn parse_time(value: &str) -> Option<std::time::Duration> {
+        let parts: Vec<&str> = value.trim().split(':').collect();
+        let (seconds, units) = parts.split_last()?;
+        if units.len() > 2 {
+            return None;
+        }
+        let seconds: f64 = seconds.parse().ok()?;
+        if !seconds.is_finite() || seconds < 0.0 || (!units.is_empty() && seconds >= 60.0) {
+            return None;
+        }
+        let mut minutes: u64 = 0;
+        for (index, unit) in units.iter().enumerate() {
+            let unit: u64 = unit.parse().ok()?;
+            // NOTE: Only the leading unit may exceed its usual range.
+            if index > 0 && unit >= 60 {
+                return None;
+            }
+            minutes = minutes * 60 + unit;
+        }
+        Some(
+            std::time::Duration::from_secs(minutes * 60)
+                + std::time::Duration::from_secs_f64(seconds),
+        )
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => start < end,
+            _ => true,
+        }
+    }
+
+    /// Returns the length of the trimmed segment for a video of the
+    /// given duration.
+    pub fn output_duration(&self, video_duration: std::time::Duration) -> std::time::Duration {
+        let start = self.start.unwrap_or_default().min(video_duration);
+        let end = self.end.unwrap_or(video_duration).min(video_duration);
+        end.saturating_sub(start)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Crop {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dither {
+    None,
+    Bayer,
+    FloydSteinberg,
+    #[default]
+    Sierra,
+}
+
+impl Dither {
+    pub const ALL: [Dither; 4] = [
+        Dither::None,
+        Dither::Bayer,
+        Dither::FloydSteinberg,
+        Dither::Sierra,
+    ];
+
+    fn ffmpeg_name(&self) -> &'static str {
+        match self {
+            Dither::None => "none",
+            Dither::Bayer => "bayer",
+            Dither::FloydSteinberg => "floyd_steinberg",
+            Dither::Sierra => "sierra2_4a",
+        }
+    }
+}
+
+impl std::fmt::Display for Dither {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dither::None => write!(f, "None"),
+            Dither::Bayer => write!(f, "Bayer"),
+            Dither::FloydSteinberg => write!(f, "Floyd-Steinberg"),
+            Dither::Sierra => write!(f, "Sierra"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StatsMode {
+    #[default]
+    Full,
+    Diff,
+    Single,
+}
+
+impl StatsMode {
+    pub const ALL: [StatsMode; 3] = [StatsMode::Full, StatsMode::Diff, StatsMode::Single];
+
+    fn ffmpeg_name(&self) -> &'static str {
+        match self {
+            StatsMode::Full => "full",
+            StatsMode::Diff => "diff",
+            StatsMode::Single => "single",
+        }
+    }
+}
+
+impl std::fmt::Display for StatsMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatsMode::Full => write!(f, "Full"),
+            StatsMode::Diff => write!(f, "Diff"),
+            StatsMode::Single => write!(f, "Single"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette {
+    pub max_colors: u16,
+    pub dither: Dither,
+    pub bayer_scale: u8,
+    pub stats_mode: StatsMode,
+}
+
+impl Palette {
+    pub const MIN_COLORS: u16 = 2;
+    pub const MAX_COLORS: u16 = 256;
+    pub const MAX_BAYER_SCALE: u8 = 5;
+
+    fn palettegen(&self) -> String {
+        format!(
+            "palettegen=max_colors={}:stats_mode={}",
+            self.max_colors.clamp(Self::MIN_COLORS, Self::MAX_COLORS),
+            self.stats_mode.ffmpeg_name()
+        )
+    }
+
+    fn paletteuse(&self) -> String {
+        let mut filter = format!("paletteuse=dither={}", self.dither.ffmpeg_name());
+        if self.dither == Dither::Bayer {
+            filter.push_str(&format!(
+                ":bayer_scale={}",
+                self.bayer_scale.min(Self::MAX_BAYER_SCALE)
+            ));
+        }
+        // NOTE: With the "single" stats mode, a new palette is generated for
+        // each frame, so `paletteuse` must be told to expect that.
+        if self.stats_mode == StatsMode::Single {
+            filter.push_str(":new=1");
+        }
+        filter
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            max_colors: Self::MAX_COLORS,
+            dither: Default::default(),
+            bayer_scale: 2,
+            stats_mode: Default::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Gif,
+    WebP,
+    Apng,
+}
+
+impl Format {
+    pub const ALL: [Format; 3] = [Format::Gif, Format::WebP, Format::Apng];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Gif => "gif",
+            Format::WebP => "webp",
+            Format::Apng => "png",
+        }
+    }
+
+    /// Parses a format from either its name or its file extension.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "gif" => Some(Format::Gif),
+            "webp" => Some(Format::WebP),
+            "apng" | "png" => Some(Format::Apng),
+            _ => None,
+        }
+    }
+
+    /// Whether FFmpeg needs a seekable output (i.e. a file rather than a
+    /// pipe) to properly finalize the container.
+    fn requires_file_output(&self) -> bool {
+        match self {
+            Format::Gif => false,
+            Format::WebP | Format::Apng => true,
+        }
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::Gif => write!(f, "GIF"),
+            Format::WebP => write!(f, "WebP"),
+            Format::Apng => write!(f, "APNG"),
+        }
+    }
+}
+
+/// A clockwise rotation of the frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    None,
+    Quarter,
+    Half,
+    ThreeQuarters,
+}
+
+impl Rotation {
+    /// The rotation by another 90 degrees.
+    pub fn next(&self) -> Self {
+        match self {
+            Rotation::None => Rotation::Quarter,
+            Rotation::Quarter => Rotation::Half,
+            Rotation::Half => Rotation::ThreeQuarters,
+            Rotation::ThreeQuarters => Rotation::None,
+        }
+    }
+
+    pub fn degrees(&self) -> u16 {
+        match self {
+            Rotation::None => 0,
+            Rotation::Quarter => 90,
+            Rotation::Half => 180,
+            Rotation::ThreeQuarters => 270,
+        }
+    }
+}
+
+impl std::fmt::Display for Rotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}°", self.degrees())
+    }
+}
+
+/// Rotates, then flips, the (cropped) frames. This comes on top of the
+/// rotation stored in the video's metadata, which FFmpeg already applies
+/// when decoding it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Transform {
+    pub rotation: Rotation,
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+}
+
+impl Transform {
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the frames' width and height get swapped.
+    pub fn swaps_dimensions(&self) -> bool {
+        matches!(self.rotation, Rotation::Quarter | Rotation::ThreeQuarters)
+    }
+
+    fn filters(&self) -> Vec<&'static str> {
+        let mut filters = match self.rotation {
+            Rotation::None => vec![],
+            Rotation::Quarter => vec!["transpose=clock"],
+            Rotation::Half => vec!["hflip", "vflip"],
+            Rotation::ThreeQuarters => vec!["transpose=cclock"],
+        };
+        if self.flip_horizontal {
+            filters.push("hflip");
+        }
+        if self.flip_vertical {
+            filters.push("vflip");
+        }
+        filters
+    }
+}
+
+impl std::fmt::Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = vec![];
+        if self.rotation != Rotation::None {
+            parts.push(format!("rotated {}", self.rotation));
+        }
+        if self.flip_horizontal {
+            parts.push("flipped horizontally".into());
+        }
+        if self.flip_vertical {
+            parts.push("flipped vertically".into());
+        }
+        if parts.is_empty() {
+            write!(f, "unchanged")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+/// How the output's dimensions are chosen, which `Size` holds the values of.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SizeMode {
+    #[default]
+    Width,
+    Height,
+    Fit,
+    Pad,
+    Fill,
+    Percent,
+}
+
+impl SizeMode {
+    pub const ALL: [SizeMode; 6] = [
+        SizeMode::Width,
+        SizeMode::Height,
+        SizeMode::Fit,
+        SizeMode::Pad,
+        SizeMode::Fill,
+        SizeMode::Percent,
+    ];
+}
+
+impl std::fmt::Display for SizeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SizeMode::Width => write!(f, "Width"),
+            SizeMode::Height => write!(f, "Height"),
+            SizeMode::Fit => write!(f, "Fit within"),
+            SizeMode::Pad => write!(f, "Pad to"),
+            SizeMode::Fill => write!(f, "Crop to"),
+            SizeMode::Percent => write!(f, "Percent"),
+        }
+    }
+}
+
+/// The output's dimensions (in pixels, unless a percentage), which keep the
+/// (cropped) frames' aspect ratio unless padded or cropped to fill a box.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Size {
+    /// A fixed width, with the height following the aspect ratio.
+    Width(u16),
+    /// A fixed height, with the width following the aspect ratio.
+    Height(u16),
+    /// The largest size fitting within the box.
+    Fit(u16, u16),
+    /// Exactly the box's size, fitting the frames within it and padding the
+    /// remaining space (i.e. letterboxing).
+    Pad(u16, u16),
+    /// Exactly the box's size, filling it with the frames and cropping what
+    /// overflows.
+    Fill(u16, u16),
+    /// A percentage of the frames' size.
+    Percent(u16),
+}
+
+impl Size {
+    pub const MIN_DIMENSION: u16 = 1;
+
+    /// Scales the size by `factor`, without going below `min` pixels (or
+    /// percent), unless already below.
+    pub fn scaled(&self, factor: f32, min: u16) -> Self {
+        let scale = |value: u16| {
+            ((value as f32 * factor).round() as u16)
+                .max(min.min(value))
+                .max(Self::MIN_DIMENSION)
+        };
+        match *self {
+            Size::Width(width) => Size::Width(scale(width)),
+            Size::Height(height) => Size::Height(scale(height)),
+            Size::Fit(width, height) => Size::Fit(scale(width), scale(height)),
+            Size::Pad(width, height) => Size::Pad(scale(width), scale(height)),
+            Size::Fill(width, height) => Size::Fill(scale(width), scale(height)),
+            Size::Percent(percent) => Size::Percent(scale(percent)),
+        }
+    }
+
+    /// Returns the output's size for frames of the given size.
+    pub fn output_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let (width, height) = (width.max(1) as f64, height.max(1) as f64);
+        let fit = |box_width: u16, box_height: u16, increase: bool| {
+            let (x, y) = (box_width as f64 / width, box_height as f64 / height);
+            let scale = if increase { x.max(y) } else { x.min(y) };
+            (width * scale, height * scale)
+        };
+        let (output_width, output_height) = match *self {
+            Size::Width(w) => (w as f64, height * w as f64 / width),
+            Size::Height(h) => (width * h as f64 / height, h as f64),
+            Size::Fit(w, h) => fit(w, h, false),
+            Size::Pad(w, h) | Size::Fill(w, h) => (w as f64, h as f64),
+            Size::Percent(percent) => (
+                width * percent as f64 / 100.0,
+                height * percent as f64 / 100.0,
+            ),
+        };
+        (
+            (output_width.round() as u32).max(1),
+            (output_height.round() as u32).max(1),
+        )
+    }
+
+    fn filter(&self, algorithm: ScaleAlgorithm) -> String {
+        let flags = algorithm.ffmpeg_name();
+        let clamp = |value: u16| value.max(Self::MIN_DIMENSION);
+        match *self {
+            Size::Width(w) => format!("scale={}:-1:flags={}", clamp(w), flags),
+            Size::Height(h) => format!("scale=-1:{}:flags={}", clamp(h), flags),
+            Size::Fit(w, h) => format!(
+                "scale={}:{}:force_original_aspect_ratio=decrease:flags={}",
+                clamp(w),
+                clamp(h),
+                flags
+            ),
+            Size::Pad(w, h) => format!(
+                "scale={w}:{h}:force_original_aspect_ratio=decrease:flags={},pad={w}:{h}:(ow-iw)/2:(oh-ih)/2",
+                flags,
+                w = clamp(w),
+                h = clamp(h)
+            ),
+            Size::Fill(w, h) => format!(
+                "scale={w}:{h}:force_original_aspect_ratio=increase:flags={},crop={w}:{h}",
+                flags,
+                w = clamp(w),
+                h = clamp(h)
+            ),
+            Size::Percent(percent) => format!(
+                "scale=iw*{p}/100:ih*{p}/100:flags={}",
+                flags,
+                p = clamp(percent)
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for Size {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Size::Width(width) => write!(f, "{}px wide", width),
+            Size::Height(height) => write!(f, "{}px tall", height),
+            Size::Fit(width, height) => write!(f, "within {}x{}px", width, height),
+            Size::Pad(width, height) => write!(f, "{}x{}px padded", width, height),
+            Size::Fill(width, height) => write!(f, "{}x{}px cropped", width, height),
+            Size::Percent(percent) => write!(f, "{}%", percent),
+        }
+    }
+}
+
+/// The algorithm used to resize the frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScaleAlgorithm {
+    #[default]
+    Lanczos,
+    Bicubic,
+    /// Keeps hard edges, which suits pixel art.
+    Neighbor,
+}
+
+impl ScaleAlgorithm {
+    pub const ALL: [ScaleAlgorithm; 3] = [
+        ScaleAlgorithm::Lanczos,
+        ScaleAlgorithm::Bicubic,
+        ScaleAlgorithm::Neighbor,
+    ];
+
+    fn ffmpeg_name(&self) -> &'static str {
+        match self {
+            ScaleAlgorithm::Lanczos => "lanczos",
+            ScaleAlgorithm::Bicubic => "bicubic",
+            ScaleAlgorithm::Neighbor => "neighbor",
+        }
+    }
+}
+
+impl std::fmt::Display for ScaleAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScaleAlgorithm::Lanczos => write!(f, "Lanczos"),
+            ScaleAlgorithm::Bicubic => write!(f, "Bicubic"),
+            ScaleAlgorithm::Neighbor => write!(f, "Nearest neighbor"),
+        }
+    }
+}
+
+/// A playback speed multiplier, stored as a percentage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Speed(u16);
+
+impl Speed {
+    pub const ALL: [Speed; 9] = [
+        Speed(25),
+        Speed(50),
+        Speed(75),
+        Speed(100),
+        Speed(125),
+        Speed(150),
+        Speed(200),
+        Speed(300),
+        Speed(400),
+    ];
+
+    pub fn factor(&self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+}
+
+impl Default for Speed {
+    fn default() -> Self {
+        Self(100)
+    }
+}
+
+impl std::fmt::Display for Speed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x", self.factor())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    Forward,
+    Reverse,
+    /// Plays forward, then backward.
+    Boomerang,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 3] = [Direction::Forward, Direction::Reverse, Direction::Boomerang];
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Forward => write!(f, "Forward"),
+            Direction::Reverse => write!(f, "Reverse"),
+            Direction::Boomerang => write!(f, "Boomerang"),
+        }
+    }
+}
+
+/// How many times the animation plays, which gets stored in the output
+/// (e.g. in the GIF's NETSCAPE loop extension).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Loop {
+    #[default]
+    Forever,
+    /// Plays the animation the given number of times (at least once).
+    Times(u16),
+}
+
+impl Loop {
+    /// Converts a number of plays, where `0` (or none) means forever.
+    pub fn from_plays(plays: Option<u16>) -> Self {
+        match plays {
+            None | Some(0) => Loop::Forever,
+            Some(plays) => Loop::Times(plays),
+        }
+    }
+
+    /// The number of plays, where `0` means forever.
+    pub fn plays(&self) -> u16 {
+        match self {
+            Loop::Forever => 0,
+            Loop::Times(plays) => *plays,
+        }
+    }
+
+    /// The value of the GIF muxer's `-loop` option, which counts the
+    /// repetitions following the first play, and where `0` means forever
+    /// and `-1` means no repetition. (WebP's `-loop` and APNG's `-plays`
+    /// count every play instead, just like `plays`.)
+    fn gif_loop(&self) -> i32 {
+        match self {
+            Loop::Forever | Loop::Times(0) => 0,
+            Loop::Times(1) => -1,
+            Loop::Times(plays) => *plays as i32 - 1,
+        }
+    }
+}
+
+impl std::fmt::Display for Loop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Loop::Forever => write!(f, "forever"),
+            Loop::Times(1) => write!(f, "once"),
+            Loop::Times(plays) => write!(f, "{} times", plays),
+        }
+    }
+}
+
+/// Image files converted as an animation's frames, one after another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageSequence {
+    pub paths: Vec<std::path::PathBuf>,
+    pub frame_delay: std::time::Duration,
+    /// Each image's own delay (e.g. of edited GIF frames), replacing
+    /// `frame_delay` when set.
+    pub frame_delays: Option<Vec<std::time::Duration>>,
+}
+
+impl ImageSequence {
+    pub fn duration(&self) -> std::time::Duration {
+        (0..self.paths.len()).map(|index| self.delay(index)).sum()
+    }
+
+    fn delay(&self, index: usize) -> std::time::Duration {
+        self.frame_delays
+            .as_ref()
+            .and_then(|delays| delays.get(index).copied())
+            .unwrap_or(self.frame_delay)
+    }
+
+    /// The frame rate at which each image becomes exactly one frame.
+    fn frame_rate(&self) -> f64 {
+        1.0 / self.frame_delay.as_secs_f64().max(0.001)
+    }
+
+    /// Returns the script listing the images for FFmpeg's `concat` demuxer.
+    fn concat_script(&self) -> String {
+        let mut script = String::from("ffconcat version 1.0\n");
+        for (index, path) in self.paths.iter().enumerate() {
+            // NOTE: Within quotes, a quote must be closed, escaped and reopened.
+            let path = path.to_string_lossy().replace('\'', "'\\''");
+            // NOTE: Frames without any delay would share their timestamp with
+            // the next one, and get dropped. GIF viewers display delays of up
+            // to 10 ms the same way, so that is used instead.
+            let delay = self.delay(index).max(MIN_IMAGE_DELAY);
+            script.push_str(&format!(
+                "file '{}'\nduration {:.3}\n",
+                path,
+                delay.as_secs_f64()
+            ));
+        }
+        script
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Input {
+    Video(String),
+    Images(ImageSequence),
+}
+
+#[derive(Clone, Debug)]
+pub struct Settings {
+    input: Input,
+    ffmpeg_path: Option<String>,
+    size: Size,
+    scaling: ScaleAlgorithm,
+    fps: u8,
+    trim: Trim,
+    crop: Option<Crop>,
+    transform: Transform,
+    palette: Palette,
+    format: Format,
+    speed: Speed,
+    direction: Direction,
+    looping: Loop,
+    overlay: Option<overlay::Overlay>,
+}
+
+impl Settings {
+    /// Creates settings for an output of the given width, which the `size`
+    /// builder may replace with other dimensions.
+    pub fn new(input: Input, width: u16, fps: u8) -> Self {
+        Self {
+            input,
+            ffmpeg_path: None,
+            size: Size::Width(width),
+            scaling: Default::default(),
+            fps,
+            trim: Default::default(),
+            crop: None,
+            transform: Default::default(),
+            palette: Default::default(),
+            format: Default::default(),
+            speed: Default::default(),
+            direction: Default::default(),
+            looping: Default::default(),
+            overlay: None,
+        }
+    }
+
+    pub fn ffmpeg_path(self, ffmpeg_path: impl Into<String>) -> Self {
+        Self {
+            ffmpeg_path: Some(ffmpeg_path.into()),
+            ..self
+        }
+    }
+
+    pub fn size(self, size: Size) -> Self {
+        Self { size, ..self }
+    }
+
+    pub fn scaling(self, scaling: ScaleAlgorithm) -> Self {
+        Self { scaling, ..self }
+    }
+
+    pub fn fps(self, fps: u8) -> Self {
+        Self { fps, ..self }
+    }
+
+    pub fn trim(self, trim: Trim) -> Self {
+        Self { trim, ..self }
+    }
+
+    pub fn crop(self, crop: Option<Crop>) -> Self {
+        Self { crop, ..self }
+    }
+
+    pub fn transform(self, transform: Transform) -> Self {
+        Self { transform, ..self }
+    }
+
+    pub fn palette(self, palette: Palette) -> Self {
+        Self { palette, ..self }
+    }
+
+    pub fn format(self, format: Format) -> Self {
+        Self { format, ..self }
+    }
+
+    pub fn speed(self, speed: Speed) -> Self {
+        Self { speed, ..self }
+    }
+
+    pub fn direction(self, direction: Direction) -> Self {
+        Self { direction, ..self }
+    }
+
+    pub fn looping(self, looping: Loop) -> Self {
+        Self { looping, ..self }
+    }
+
+    pub fn overlay(self, overlay: Option<overlay::Overlay>) -> Self {
+        Self { overlay, ..self }
+    }
+
+    /// Whether the palette settings affect the output, which is only the
+    /// case for GIFs.
+    pub fn uses_palette(&self) -> bool {
+        self.format == Format::Gif
+    }
+
+    /// Whether the frame rate affects the output, which is not the case for
+    /// image sequences (whose frame delay is used instead).
+    pub fn uses_fps(&self) -> bool {
+        matches!(self.input, Input::Video(_))
+    }
+
+    /// The input's duration, when known without asking FFmpeg.
+    fn input_duration(&self) -> Option<std::time::Duration> {
+        match &self.input {
+            Input::Video(_) => None,
+            Input::Images(sequence) => Some(sequence.duration()),
+        }
+    }
+
+    /// Returns the duration of the output for a video of the given
+    /// duration, which is used to compute the conversion's progress.
+    fn output_duration(&self, video_duration: std::time::Duration) -> std::time::Duration {
+        let duration = self
+            .trim
+            .output_duration(video_duration)
+            .div_f64(self.speed.factor());
+        match self.direction {
+            Direction::Boomerang => duration * 2,
+            Direction::Forward | Direction::Reverse => duration,
+        }
+    }
+
+    fn program(&self) -> &str {
+        self.ffmpeg_path.as_deref().unwrap_or(DEFAULT_FFMPEG_PATH)
+    }
+
+    fn filter(&self) -> String {
+        let mut filters: Vec<String> = vec![];
+        if let Some(crop) = self.crop {
+            filters.push(format!(
+                "crop={}:{}:{}:{}",
+                crop.width, crop.height, crop.x, crop.y
+            ));
+        }
+        // NOTE: The transform comes after the crop, whose coordinates are
+        // those of the (untransformed) preview, and before the scaling, so
+        // that the output's size applies to the transformed frames.
+        filters.extend(self.transform.filters().into_iter().map(String::from));
+        if self.speed != Speed::default() {
+            filters.push(format!("setpts=PTS/{}", self.speed.factor()));
+        }
+        match &self.input {
+            Input::Video(_) => filters.push(format!("fps={}", self.fps)),
+            // NOTE: Images keep their frame delay (divided by the speed)
+            // rather than being resampled to the output's frame rate, so
+            // that none of them gets dropped or duplicated.
+            Input::Images(sequence) if sequence.frame_delays.is_none() => filters.push(format!(
+                "fps={}",
+                sequence.frame_rate() * self.speed.factor()
+            )),
+            // NOTE: Images with their own delays are kept as variable frame
+            // rate frames, whose timestamps the muxer turns into delays.
+            Input::Images(_) => {}
+        }
+        filters.push(self.size.filter(self.scaling));
+        if let Some(overlay) = self.overlay.as_ref() {
+            filters.push(overlay.filter());
+        }
+
+        // NOTE: The `reverse` filter buffers every frame in memory, so it is
+        // applied once the frames have been resampled and scaled down.
+        let chain = match self.direction {
+            Direction::Forward => filters.join(","),
+            Direction::Reverse => format!("{},reverse", filters.join(",")),
+            // NOTE: The reversed copy skips its first frame, which is the
+            // forward part's last one.
+            Direction::Boomerang => format!(
+                "{},split[f][r];[r]reverse,trim=start_frame=1[b];[f][b]concat=n=2:v=1:a=0",
+                filters.join(",")
+            ),
+        };
+
+        match self.format {
+            Format::Gif => format!(
+                "{},split[s0][s1];[s0]{}[p];[s1][p]{}",
+                chain,
+                self.palette.palettegen(),
+                self.palette.paletteuse()
+            ),
+            Format::WebP | Format::Apng => chain,
+        }
+    }
+
+    fn output_arguments(&self) -> Vec<String> {
+        match self.format {
+            Format::Gif => vec![
+                "-loop".into(),
+                format!("{}", self.looping.gif_loop()),
+                "-f".into(),
+                "gif".into(),
+            ],
+            Format::WebP => vec![
+                "-c:v".into(),
+                "libwebp".into(),
+                "-lossless".into(),
+                "0".into(),
+                "-quality".into(),
+                format!("{}", WEBP_QUALITY),
+                "-loop".into(),
+                format!("{}", self.looping.plays()),
+                "-f".into(),
+                "webp".into(),
+            ],
+            Format::Apng => vec![
+                "-plays".into(),
+                format!("{}", self.looping.plays()),
+                "-f".into(),
+                "apng".into(),
+            ],
+        }
+    }
+
+    fn arguments(&self, input: &str, output: &str) -> Vec<String> {
+        let mut arguments: Vec<String> = vec!["-hide_banner".into(), "-nostdin".into()];
+
+        if let Some(start) = self.trim.start {
+            arguments.push("-ss".into());
+            arguments.push(format!("{:.3}", start.as_secs_f64()));
+        }
+
+        // NOTE: The length is given as an input option, so that it limits
+        // the part of the video that is read rather than the output, whose
+        // duration changes with the speed and direction.
+        if let Some(end) = self.trim.end {
+            let length = end.saturating_sub(self.trim.start.unwrap_or_default());
+            arguments.push("-t".into());
+            arguments.push(format!("{:.3}", length.as_secs_f64()));
+        }
+
+        if let Input::Images(_) = self.input {
+            arguments.extend(["-f", "concat", "-safe", "0"].map(String::from));
+        }
+        arguments.push("-i".into());
+        arguments.push(input.into());
+
+        arguments.push("-vf".into());
+        arguments.push(self.filter());
+        arguments.extend(self.output_arguments());
+        arguments.push("-y".into());
+        arguments.push(output.into());
+
+        arguments
+    }
+}
+
+/// A still frame extracted from a video, encoded as PNG.
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub png_data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub video_duration: Option<std::time::Duration>,
+}
+
+/// Extracts the first frame of a video, so that it can be previewed before
+/// starting a conversion job.
+pub async fn extract_frame(
+    ffmpeg_path: Option<String>,
+    input_path: String,
+) -> Result<Frame, Error> {
+    let program = ffmpeg_path.as_deref().unwrap_or(DEFAULT_FFMPEG_PATH);
+    log::debug!(target: LOG_TARGET, "Extracting first frame of {:?} using {}", input_path, program);
+
+    let output = tokio::process::Command::new(program)
+        .args(["-hide_banner", "-nostdin", "-i"])
+        .arg(&input_path)
+        .args([
+            "-frames:v",
+            "1",
+            "-f",
+            "image2pipe",
+            "-c:v",
+            "png",
+            "pipe:1",
+        ])
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| Error::Spawn(e.to_string()))?;
+
+    let log = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        let last_log_line = log
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .last()
+            .unwrap_or_default();
+        return Err(Error::Ffmpeg(last_log_line.into()));
+    }
+
+    let (width, height) = png_dimensions(&output.stdout).ok_or(Error::EmptyStdout)?;
+
+    Ok(Frame {
+        png_data: output.stdout,
+        width,
+        height,
+        video_duration: log.lines().find_map(|line| parse_field(line, "Duration: ")),
+    })
+}
+
+/// Reads the image dimensions from a PNG file's `IHDR` chunk.
+fn png_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    if data.len() < 24 || &data[1..4] != b"PNG" {
+        return None;
+    }
+    let width = u32::from_be_bytes(data[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(data[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+pub fn temp_file_path(extension: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!(
+        "iced-gif-maker-{}-{}.{}",
+        std::process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+        extension
+    ))
+}
+
+pub struct Converter {
+    command_rx: CommandReceiver,
+    message_tx: MessageSender,
+}
+
+impl Converter {
+    pub fn new_with_channels() -> (Self, CommandSender, MessageReceiver) {
+        let (command_tx, command_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (message_tx, message_rx) = tokio::sync::mpsc::unbounded_channel();
+        (
+            Self {
+                command_rx,
+                message_tx,
+            },
+            command_tx,
+            message_rx,
+        )
+    }
+
+    /// Runs a conversion on the current thread and returns its output,
+    /// without reporting its progress nor allowing to cancel it.
+    pub fn convert_blocking(settings: &Settings) -> Result<Vec<u8>, Error> {
+        let (mut converter, _command_tx, _message_rx) = Self::new_with_channels();
+        converter.run(settings)
+    }
+
+    /// Runs the conversion job to completion, blocking the current thread.
+    /// A `Message::Done` is always sent last, whatever the outcome.
+    pub fn convert(mut self, settings: Settings) {
+        let message = match self.run(&settings) {
+            Ok(data) => Message::Success(data),
+            Err(e) => Message::Error(e),
+        };
+        self.send(message);
+        self.send(Message::Done);
+    }
+
+    fn send(&self, message: Message) {
+        if let Err(e) = self.message_tx.send(message) {
+            log::warn!(target: LOG_TARGET, "Failed to send message to worker: {:?}", e);
+        }
+    }
+
+    fn run(&mut self, settings: &Settings) -> Result<Vec<u8>, Error> {
+        if settings.overlay.is_some() {
+            overlay::write_font_file().map_err(|e| Error::Io(e.to_string()))?;
+        }
+
+        let sequence = match &settings.input {
+            Input::Video(path) => return self.run_with_input(settings, path),
+            Input::Images(sequence) => sequence,
+        };
+
+        // NOTE: Image sequences are read through a script listing them,
+        // which only exists for the duration of the conversion.
+        let script_path = temp_file_path("ffconcat");
+        std::fs::write(&script_path, sequence.concat_script())
+            .map_err(|e| Error::Io(e.to_string()))?;
+        let result = self.run_with_input(settings, &script_path.to_string_lossy());
+        if let Err(e) = std::fs::remove_file(&script_path) {
+            log::debug!(target: LOG_TARGET, "Could not remove temporary script file {:?}: {:?}", script_path, e);
+        }
+        result
+    }
+
+    fn run_with_input(&mut self, settings: &Settings, input: &str) -> Result<Vec<u8>, Error> {
+        if !settings.format.requires_file_output() {
+            return self.run_ffmpeg(settings, input, "pipe:1");
+        }
+
+        let output_path = temp_file_path(settings.format.extension());
+        let result = self
+            .run_ffmpeg(settings, input, &output_path.to_string_lossy())
+            .and_then(|_| std::fs::read(&output_path).map_err(|e| Error::Io(e.to_string())));
+        if let Err(e) = std::fs::remove_file(&output_path) {
+            log::debug!(target: LOG_TARGET, "Could not remove temporary output file {:?}: {:?}", output_path, e);
+        }
+
+        match result {
+            Ok(data) if data.is_empty() => Err(Error::EmptyStdout),
+            result => result,
+        }
+    }
+
+    /// Runs FFmpeg and returns whatever it wrote to `stdout`.
+    fn run_ffmpeg(
+        &mut self,
+        settings: &Settings,
+        input: &str,
+        output: &str,
+    ) -> Result<Vec<u8>, Error> {
+        let arguments = settings.arguments(input, output);
+        log::debug!(target: LOG_TARGET, "Spawning FFmpeg: {} {:?}", settings.program(), arguments);
+
+        let mut child = std::process::Command::new(settings.program())
+            .args(arguments)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::Spawn(e.to_string()))?;
+
+        let mut stdout = child.stdout.take().expect("stdout should be piped");
+        let stdout_handle = std::thread::spawn(move || {
+            let mut data = Vec::new();
+            stdout.read_to_end(&mut data).map(|_| data)
+        });
+
+        let stderr = child.stderr.take().expect("stderr should be piped");
+        let message_tx = self.message_tx.clone();
+        let stderr_settings = settings.clone();
+        let stderr_handle =
+            std::thread::spawn(move || read_stderr(stderr, stderr_settings, message_tx));
+
+        let cancelled = loop {
+            if let Ok(Command::Cancel) = self.command_rx.try_recv() {
+                log::debug!(target: LOG_TARGET, "Cancellation requested, killing FFmpeg process...");
+                if let Err(e) = child.kill() {
+                    log::warn!(target: LOG_TARGET, "Failed to kill FFmpeg process: {:?}", e);
+                }
+                break true;
+            }
+            match child.try_wait() {
+                Ok(Some(_)) => break false,
+                Ok(None) => std::thread::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS)),
+                Err(e) => {
+                    let _ = child.kill();
+                    return Err(Error::Io(e.to_string()));
+                }
+            }
+        };
+
+        let status = child.wait().map_err(|e| Error::Io(e.to_string()))?;
+        let data = stdout_handle
+            .join()
+            .map_err(|_| Error::Io("stdout reader panicked".into()))?
+            .map_err(|e| Error::Io(e.to_string()))?;
+        let last_log_line = stderr_handle.join().unwrap_or_default();
+
+        if cancelled {
+            return Err(Error::Cancelled);
+        }
+        if !status.success() {
+            return Err(Error::Ffmpeg(last_log_line));
+        }
+        if data.is_empty() && !settings.format.requires_file_output() {
+            return Err(Error::EmptyStdout);
+        }
+        Ok(data)
+    }
+}
+
+/// Reads FFmpeg's `stderr` until it closes, forwarding the video duration
+/// and the progress to the worker. Returns the last line that was not a
+/// progress report, which usually explains why FFmpeg failed.
+fn read_stderr(
+    stderr: std::process::ChildStderr,
+    settings: Settings,
+    message_tx: MessageSender,
+) -> String {
+    let reader = std::io::BufReader::new(stderr);
+    let mut output_duration: Option<std::time::Duration> = None;
+
+    // NOTE: The duration reported by FFmpeg is not reliable for image
+    // sequences, but it is known in advance.
+    if let Some(duration) = settings.input_duration() {
+        output_duration = Some(settings.output_duration(duration));
+        let _ = message_tx.send(Message::VideoDuration(duration));
+    }
+    let mut last_log_line = String::new();
+    let mut line: Vec<u8> = Vec::new();
+
+    // NOTE: FFmpeg terminates its progress lines with '\r' instead of '\n'.
+    let mut handle_line = |line: &[u8]| {
+        let text = String::from_utf8_lossy(line).trim().to_string();
+        if text.is_empty() {
+            return;
+        }
+
+        if output_duration.is_none() {
+            if let Some(duration) = parse_field(&text, "Duration: ") {
+                output_duration = Some(settings.output_duration(duration));
+                let _ = message_tx.send(Message::VideoDuration(duration));
+            }
+        }
+
+        if let Some(time) = parse_field(&text, "time=") {
+            if let Some(total) = output_duration.filter(|d| !d.is_zero()) {
+                let progress = (time.as_secs_f64() / total.as_secs_f64()).min(1.0);
+                let _ = message_tx.send(Message::Progress(progress));
+            }
+        } else {
+            last_log_line = text;
+        }
+    };
+
+    for byte in reader.bytes() {
+        let Ok(byte) = byte else {
+            break;
+        };
+        if byte == b'\r' || byte == b'\n' {
+            handle_line(&line);
+            line.clear();
+        } else {
+            line.push(byte);
+        }
+    }
+    handle_line(&line);
+
+    last_log_line
+}
+
+fn parse_field(text: &str, key: &str) -> Option<std::time::Duration> {
+    let start = text.find(key)? + key.len();
+    let value = text[start..].split(|c| c == ',' || c == ' ').next()?;
+    parse_timestamp(value)
+}
+
+/// Parses timestamps in FFmpeg's `HH:MM:SS.ss` format.
+fn parse_timestamp(value: &str) -> Option<std::time::Duration> {
+    let mut parts = value.split(':');
+    let hours: u64 = parts.next()?.parse().ok()?;
+    let minutes: u64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || seconds < 0.0 {
+        return None;
+    }
+    Some(
+        std::time::Duration::from_secs(hours * 3600 + minutes * 60)
+            + std::time::Duration::from_secs_f64(seconds),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output_option(format: Format, looping: Loop, option: &str) -> Option<String> {
+        let arguments = Settings::new(Input::Video("input.mp4".into()), 480, 10)
+            .format(format)
+            .looping(looping)
+            .output_arguments();
+        option_value(&arguments, option)
+    }
+
+    fn option_value(arguments: &[String], option: &str) -> Option<String> {
+        let index = arguments.iter().position(|argument| argument == option)?;
+        arguments.get(index + 1).cloned()
+    }
+
+    fn seconds(seconds: f64) -> std::time::Duration {
+        std::time::Duration::from_secs_f64(seconds)
+    }
+
+    #[test]
+    fn trim_parses_times() {
+        assert_eq!(Trim::parse_time("12"), Some(seconds(12.0)));
+        assert_eq!(Trim::parse_time(" 2.5 "), Some(seconds(2.5)));
+        assert_eq!(Trim::parse_time("90.25"), Some(seconds(90.25)));
+        assert_eq!(Trim::parse_time("1:02.5"), Some(seconds(62.5)));
+        assert_eq!(Trim::parse_time("1:00:00"), Some(seconds(3600.0)));
+        assert_eq!(Trim::parse_time("0:01:02.5"), Some(seconds(62.5)));
+        assert_eq!(Trim::parse_time(""), None);
+        assert_eq!(Trim::parse_time("-1"), None);
+        assert_eq!(Trim::parse_time("abc"), None);
+        assert_eq!(Trim::parse_time("1:60"), None);
+        assert_eq!(Trim::parse_time("1:60:00"), None);
+        assert_eq!(Trim::parse_time("1:2:3:4"), None);
+        assert_eq!(Trim::parse_time("inf"), None);
+    }
+
+    #[test]
+    fn trim_validity_and_duration() {
+        let trim = Trim {
+            start: Some(seconds(1.5)),
+            end: Some(seconds(4.0)),
+        };
+        assert!(trim.is_valid());
+        assert_eq!(trim.output_duration(seconds(10.0)), seconds(2.5));
+        assert_eq!(trim.output_duration(seconds(3.0)), seconds(1.5));
+        assert!(!Trim {
+            start: Some(seconds(4.0)),
+            end: Some(seconds(4.0)),
+        }
+        .is_valid());
+        assert_eq!(
+            Trim::default().output_duration(seconds(10.0)),
+            seconds(10.0)
+        );
+    }
+
+    #[test]
+    fn trim_arguments() {
+        let settings = |trim| {
+            Settings::new(Input::Video("input.mp4".into()), 480, 10)
+                .trim(trim)
+                .arguments("input.mp4", "output.gif")
+        };
+
+        let arguments = settings(Trim {
+            start: Some(seconds(1.5)),
+            end: Some(seconds(4.25)),
+        });
+        assert_eq!(option_value(&arguments, "-ss").as_deref(), Some("1.500"));
+        assert_eq!(option_value(&arguments, "-t").as_deref(), Some("2.750"));
+        // NOTE: Both are input options, given before the input.
+        let input = arguments.iter().position(|a| a == "-i").unwrap();
+        assert!(arguments.iter().position(|a| a == "-ss").unwrap() < input);
+        assert!(arguments.iter().position(|a| a == "-t").unwrap() < input);
+
+        let arguments = settings(Trim {
+            start: None,
+            end: Some(seconds(3.0)),
+        });
+        assert_eq!(option_value(&arguments, "-ss"), None);
+        assert_eq!(option_value(&arguments, "-t").as_deref(), Some("3.000"));
+
+        let arguments = settings(Trim::default());
+        assert_eq!(option_value(&arguments, "-ss"), None);
+        assert_eq!(option_value(&arguments, "-t"), None);
+    }
+
+    #[test]
+    fn crop_comes_first_in_the_filter_chain() {
+        let filter = Settings::new(Input::Video("input.mp4".into()), 480, 10)
+            .crop(Some(Crop {
+                x: 10,
+                y: 20,
+                width: 300,
+                height: 200,
+            }))
+            .filter();
+        assert!(filter.starts_with("crop=300:200:10:20,"), "{}", filter);
+
+        let filter = Settings::new(Input::Video("input.mp4".into()), 480, 10).filter();
+        assert!(!filter.contains("crop="), "{}", filter);
+    }
+
+    #[test]
+    fn images_keep_their_own_delays() {
+        let sequence = ImageSequence {
+            paths: vec!["a.png".into(), "b.png".into(), "c.png".into()],
+            frame_delay: seconds(0.1),
+            frame_delays: Some(vec![seconds(0.5), seconds(0.0), seconds(0.04)]),
+        };
+        assert_eq!(sequence.duration(), seconds(0.54));
+        assert_eq!(
+            sequence.concat_script(),
+            "ffconcat version 1.0\n\
+             file 'a.png'\nduration 0.500\n\
+             file 'b.png'\nduration 0.010\n\
+             file 'c.png'\nduration 0.040\n"
+        );
+        let filter = Settings::new(Input::Images(sequence), 480, 10).filter();
+        assert!(!filter.contains("fps="), "{}", filter);
+    }
+
+    #[test]
+    fn size_filters() {
+        assert_eq!(
+            Size::Width(480).filter(ScaleAlgorithm::Lanczos),
+            "scale=480:-1:flags=lanczos"
+        );
+        assert_eq!(
+            Size::Height(0).filter(ScaleAlgorithm::Neighbor),
+            "scale=-1:1:flags=neighbor"
+        );
+        assert_eq!(
+            Size::Fit(480, 360).filter(ScaleAlgorithm::Bicubic),
+            "scale=480:360:force_original_aspect_ratio=decrease:flags=bicubic"
+        );
+        assert_eq!(
+            Size::Pad(480, 360).filter(ScaleAlgorithm::Lanczos),
+            "scale=480:360:force_original_aspect_ratio=decrease:flags=lanczos,pad=480:360:(ow-iw)/2:(oh-ih)/2"
+        );
+        assert_eq!(
+            Size::Fill(480, 360).filter(ScaleAlgorithm::Lanczos),
+            "scale=480:360:force_original_aspect_ratio=increase:flags=lanczos,crop=480:360"
+        );
+        assert_eq!(
+            Size::Percent(50).filter(ScaleAlgorithm::Lanczos),
+            "scale=iw*50/100:ih*50/100:flags=lanczos"
+        );
+    }
+
+    #[test]
+    fn size_output_sizes() {
+        assert_eq!(Size::Width(480).output_size(1920, 1080), (480, 270));
+        assert_eq!(Size::Height(360).output_size(1920, 1080), (640, 360));
+        assert_eq!(Size::Fit(480, 480).output_size(1080, 1920), (270, 480));
+        assert_eq!(Size::Pad(480, 480).output_size(1920, 1080), (480, 480));
+        assert_eq!(Size::Fill(480, 480).output_size(1920, 1080), (480, 480));
+        assert_eq!(Size::Percent(25).output_size(1920, 1080), (480, 270));
+        assert_eq!(Size::Width(480).output_size(0, 0), (480, 480));
+    }
+
+    #[test]
+    fn size_scaling_keeps_a_minimum() {
+        assert_eq!(Size::Width(480).scaled(0.5, 100), Size::Width(240));
+        assert_eq!(Size::Width(150).scaled(0.5, 100), Size::Width(100));
+        assert_eq!(Size::Width(80).scaled(0.5, 100), Size::Width(80));
+        assert_eq!(Size::Fit(480, 120).scaled(0.5, 100), Size::Fit(240, 100));
+        assert_eq!(Size::Percent(1).scaled(0.1, 10), Size::Percent(1));
+    }
+
+    #[test]
+    fn transform_filters() {
+        let transform = |rotation, flip_horizontal, flip_vertical| Transform {
+            rotation,
+            flip_horizontal,
+            flip_vertical,
+        };
+        assert!(transform(Rotation::None, false, false).filters().is_empty());
+        assert_eq!(
+            transform(Rotation::Quarter, false, false).filters(),
+            ["transpose=clock"]
+        );
+        assert_eq!(
+            transform(Rotation::Half, false, false).filters(),
+            ["hflip", "vflip"]
+        );
+        assert_eq!(
+            transform(Rotation::ThreeQuarters, true, true).filters(),
+            ["transpose=cclock", "hflip", "vflip"]
+        );
+        assert!(transform(Rotation::Quarter, false, false).swaps_dimensions());
+        assert!(!transform(Rotation::Half, true, false).swaps_dimensions());
+        assert_eq!(Rotation::ThreeQuarters.next(), Rotation::None);
+    }
+
+    #[test]
+    fn transform_comes_between_crop_and_scale() {
+        let filter = Settings::new(Input::Video("input.mp4".into()), 480, 10)
+            .crop(Some(Crop {
+                x: 0,
+                y: 0,
+                width: 300,
+                height: 200,
+            }))
+            .transform(Transform {
+                rotation: Rotation::Quarter,
+                flip_horizontal: true,
+                flip_vertical: false,
+            })
+            .filter();
+        assert!(
+            filter.starts_with("crop=300:200:0:0,transpose=clock,hflip,fps=10,scale=480:-1:"),
+            "{}",
+            filter
+        );
+    }
+
+    #[test]
+    fn gif_loop_counts_repetitions() {
+        let gif_loop = |looping| output_option(Format::Gif, looping, "-loop");
+        assert_eq!(gif_loop(Loop::Forever).as_deref(), Some("0"));
+        assert_eq!(gif_loop(Loop::Times(1)).as_deref(), Some("-1"));
+        assert_eq!(gif_loop(Loop::Times(2)).as_deref(), Some("1"));
+        assert_eq!(gif_loop(Loop::Times(5)).as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn webp_loop_counts_plays() {
+        let webp_loop = |looping| output_option(Format::WebP, looping, "-loop");
+        assert_eq!(webp_loop(Loop::Forever).as_deref(), Some("0"));
+        assert_eq!(webp_loop(Loop::Times(1)).as_deref(), Some("1"));
+        assert_eq!(webp_loop(Loop::Times(5)).as_deref(), Some("5"));
+    }
+
+    #[test]
+    fn apng_plays_counts_plays() {
+        let apng_plays = |looping| output_option(Format::Apng, looping, "-plays");
+        assert_eq!(apng_plays(Loop::Forever).as_deref(), Some("0"));
+        assert_eq!(apng_plays(Loop::Times(1)).as_deref(), Some("1"));
+        assert_eq!(apng_plays(Loop::Times(5)).as_deref(), Some("5"));
+    }
+
+    #[test]
+    fn loop_from_plays() {
+        assert_eq!(Loop::from_plays(None), Loop::Forever);
+        assert_eq!(Loop::from_plays(Some(0)), Loop::Forever);
+        assert_eq!(Loop::from_plays(Some(3)), Loop::Times(3));
+        assert_eq!(Loop::Times(3).plays(), 3);
+        assert_eq!(Loop::Forever.plays(), 0);
+    }
+}
@@ -0,0 +1,358 @@
+// [custom widget example](https://github.com/iced-rs/iced/blob/0.10/examples/custom_widget/src/main.rs)
+
+use iced::advanced::layout;
+use iced::advanced::renderer;
+use iced::advanced::widget::{tree, Tree};
+use iced::advanced::{Clipboard, Layout, Shell, Widget};
+
+use crate::converter::Crop;
+use crate::overlay::Overlay;
+
+const SHADE_COLOR: iced::Color = iced::Color {
+    r: 0.0,
+    g: 0.0,
+    b: 0.0,
+    a: 0.6,
+};
+const BORDER_COLOR: iced::Color = iced::Color::WHITE;
+const BORDER_WIDTH: f32 = 1.5;
+
+/// Displays a still frame and lets the user drag a crop rectangle on it.
+/// The rectangle is reported in the frame's pixel coordinates.
+pub struct CropSelector<'a, M> {
+    handle: iced::widget::image::Handle,
+    image_size: iced::Size<u32>,
+    crop: Option<Crop>,
+    on_change: Box<dyn Fn(Option<Crop>) -> M + 'a>,
+    /// The overlay to preview on the (cropped) frame, along with the
+    /// output's width and the font to draw it with.
+    overlay: Option<(Overlay, u16, iced::Font)>,
+}
+
+impl<'a, M> CropSelector<'a, M> {
+    pub fn new(
+        handle: iced::widget::image::Handle,
+        image_size: iced::Size<u32>,
+        crop: Option<Crop>,
+        on_change: impl Fn(Option<Crop>) -> M + 'a,
+    ) -> Self {
+        Self {
+            handle,
+            image_size,
+            crop,
+            on_change: Box::new(on_change),
+            overlay: None,
+        }
+    }
+
+    pub fn overlay(self, overlay: Option<Overlay>, output_width: u16, font: iced::Font) -> Self {
+        Self {
+            overlay: overlay.map(|overlay| (overlay, output_width, font)),
+            ..self
+        }
+    }
+
+    /// Returns the area, within `bounds`, in which the frame gets drawn,
+    /// along with the factor used to scale it.
+    fn image_bounds(&self, bounds: iced::Rectangle) -> (iced::Rectangle, f32) {
+        let image_width = self.image_size.width.max(1) as f32;
+        let image_height = self.image_size.height.max(1) as f32;
+        let scale = (bounds.width / image_width).min(bounds.height / image_height);
+        let size = iced::Size::new(image_width * scale, image_height * scale);
+        let position = iced::Point::new(
+            bounds.x + (bounds.width - size.width) / 2.0,
+            bounds.y + (bounds.height - size.height) / 2.0,
+        );
+        (iced::Rectangle::new(position, size), scale)
+    }
+
+    /// Converts a cursor position into frame pixel coordinates, clamping it
+    /// to the frame's edges.
+    fn to_image_point(&self, bounds: iced::Rectangle, position: iced::Point) -> iced::Point {
+        let (image_bounds, scale) = self.image_bounds(bounds);
+        iced::Point::new(
+            ((position.x - image_bounds.x) / scale).clamp(0.0, self.image_size.width as f32),
+            ((position.y - image_bounds.y) / scale).clamp(0.0, self.image_size.height as f32),
+        )
+    }
+
+    fn crop_between(a: iced::Point, b: iced::Point) -> Option<Crop> {
+        let x = a.x.min(b.x).round() as u32;
+        let y = a.y.min(b.y).round() as u32;
+        let width = (a.x - b.x).abs().round() as u32;
+        let height = (a.y - b.y).abs().round() as u32;
+        if width == 0 || height == 0 {
+            None
+        } else {
+            Some(Crop {
+                x,
+                y,
+                width,
+                height,
+            })
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    drag_origin: Option<iced::Point>,
+}
+
+impl<'a, M, R> Widget<M, R> for CropSelector<'a, M>
+where
+    R: renderer::Renderer
+        + iced::advanced::image::Renderer<Handle = iced::widget::image::Handle>
+        + iced::advanced::text::Renderer<Font = iced::Font>,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn width(&self) -> iced::Length {
+        iced::Length::Fill
+    }
+
+    fn height(&self) -> iced::Length {
+        iced::Length::Fill
+    }
+
+    fn layout(&self, _renderer: &R, limits: &layout::Limits) -> layout::Node {
+        let size = limits
+            .width(iced::Length::Fill)
+            .height(iced::Length::Fill)
+            .resolve(iced::Size::ZERO);
+        layout::Node::new(size)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: iced::Event,
+        layout: Layout<'_>,
+        cursor: iced::mouse::Cursor,
+        _renderer: &R,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, M>,
+        _viewport: &iced::Rectangle,
+    ) -> iced::event::Status {
+        let state = tree.state.downcast_mut::<State>();
+        let bounds = layout.bounds();
+        let (image_bounds, _) = self.image_bounds(bounds);
+
+        match event {
+            iced::Event::Mouse(iced::mouse::Event::ButtonPressed(iced::mouse::Button::Left)) => {
+                let Some(position) = cursor.position_over(image_bounds) else {
+                    return iced::event::Status::Ignored;
+                };
+                state.drag_origin = Some(self.to_image_point(bounds, position));
+                iced::event::Status::Captured
+            }
+            iced::Event::Mouse(iced::mouse::Event::CursorMoved { position }) => {
+                let Some(origin) = state.drag_origin else {
+                    return iced::event::Status::Ignored;
+                };
+                let crop = Self::crop_between(origin, self.to_image_point(bounds, position));
+                if crop.is_some() {
+                    shell.publish((self.on_change)(crop));
+                }
+                iced::event::Status::Captured
+            }
+            iced::Event::Mouse(iced::mouse::Event::ButtonReleased(iced::mouse::Button::Left)) => {
+                let Some(origin) = state.drag_origin.take() else {
+                    return iced::event::Status::Ignored;
+                };
+                // NOTE: A simple click (i.e. without dragging) clears the selection.
+                let position = cursor.position().unwrap_or(origin);
+                let crop = Self::crop_between(origin, self.to_image_point(bounds, position));
+                shell.publish((self.on_change)(crop));
+                iced::event::Status::Captured
+            }
+            _ => iced::event::Status::Ignored,
+        }
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut R,
+        _theme: &R::Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: iced::mouse::Cursor,
+        _viewport: &iced::Rectangle,
+    ) {
+        let (image_bounds, scale) = self.image_bounds(layout.bounds());
+
+        iced::advanced::image::Renderer::draw(renderer, self.handle.clone(), image_bounds);
+
+        let selection = self.crop.map(|crop| iced::Rectangle {
+            x: image_bounds.x + crop.x as f32 * scale,
+            y: image_bounds.y + crop.y as f32 * scale,
+            width: crop.width as f32 * scale,
+            height: crop.height as f32 * scale,
+        });
+
+        if let Some(selection) = selection {
+            self.draw_selection(renderer, image_bounds, selection);
+        }
+        self.draw_overlay(renderer, selection.unwrap_or(image_bounds));
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: iced::mouse::Cursor,
+        _viewport: &iced::Rectangle,
+        _renderer: &R,
+    ) -> iced::mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+        let (image_bounds, _) = self.image_bounds(layout.bounds());
+        if state.drag_origin.is_some() || cursor.is_over(image_bounds) {
+            iced::mouse::Interaction::Crosshair
+        } else {
+            iced::mouse::Interaction::default()
+        }
+    }
+}
+
+impl<'a, M> CropSelector<'a, M> {
+    fn draw_selection<R: renderer::Renderer>(
+        &self,
+        renderer: &mut R,
+        image_bounds: iced::Rectangle,
+        selection: iced::Rectangle,
+    ) {
+        // NOTE: Quads must be drawn on a new layer, otherwise they could end
+        // up underneath the image.
+        renderer.with_layer(image_bounds, |renderer| {
+            let shades = [
+                iced::Rectangle {
+                    height: selection.y - image_bounds.y,
+                    ..image_bounds
+                },
+                iced::Rectangle {
+                    y: selection.y + selection.height,
+                    height: image_bounds.y + image_bounds.height - selection.y - selection.height,
+                    ..image_bounds
+                },
+                iced::Rectangle {
+                    y: selection.y,
+                    width: selection.x - image_bounds.x,
+                    height: selection.height,
+                    ..image_bounds
+                },
+                iced::Rectangle {
+                    x: selection.x + selection.width,
+                    y: selection.y,
+                    width: image_bounds.x + image_bounds.width - selection.x - selection.width,
+                    height: selection.height,
+                },
+            ];
+
+            for bounds in shades {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds,
+                        border_radius: 0.0.into(),
+                        border_width: 0.0,
+                        border_color: iced::Color::TRANSPARENT,
+                    },
+                    SHADE_COLOR,
+                );
+            }
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: selection,
+                    border_radius: 0.0.into(),
+                    border_width: BORDER_WIDTH,
+                    border_color: BORDER_COLOR,
+                },
+                iced::Color::TRANSPARENT,
+            );
+        });
+    }
+
+    /// Draws the overlay on `region` (i.e. the part of the frame that gets
+    /// converted), scaled from the output's size.
+    fn draw_overlay<R>(&self, renderer: &mut R, region: iced::Rectangle)
+    where
+        R: renderer::Renderer + iced::advanced::text::Renderer<Font = iced::Font>,
+    {
+        let Some((overlay, output_width, font)) = self.overlay.as_ref() else {
+            return;
+        };
+        if region.width <= 0.0 || region.height <= 0.0 {
+            return;
+        }
+
+        // NOTE: The layout is computed in output pixels, then scaled to fit
+        // the region, like FFmpeg scales the frames before drawing on them.
+        let scale = region.width / (*output_width).max(1) as f32;
+        let frame = iced::Size::new(region.width / scale, region.height / scale);
+        let font_size = overlay.font_size as f32 * scale;
+        let line_height = iced::advanced::text::LineHeight::default();
+        let text = renderer.measure(
+            &overlay.text,
+            font_size,
+            line_height,
+            *font,
+            iced::Size::INFINITY,
+            iced::advanced::text::Shaping::Advanced,
+        );
+        let (origin, background) = overlay.layout(
+            frame,
+            iced::Size::new(text.width / scale, text.height / scale),
+        );
+        let to_region =
+            |x: f32, y: f32| iced::Point::new(region.x + x * scale, region.y + y * scale);
+
+        renderer.with_layer(region, |renderer| {
+            if let Some(background) = background {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: iced::Rectangle::new(
+                            to_region(background.x, background.y),
+                            iced::Size::new(background.width * scale, background.height * scale),
+                        ),
+                        border_radius: 0.0.into(),
+                        border_width: 0.0,
+                        border_color: iced::Color::TRANSPARENT,
+                    },
+                    overlay.background_color(),
+                );
+            }
+
+            renderer.fill_text(iced::advanced::text::Text {
+                content: &overlay.text,
+                bounds: iced::Rectangle::new(to_region(origin.x, origin.y), text),
+                size: font_size,
+                line_height,
+                color: overlay.color.to_iced(),
+                font: *font,
+                horizontal_alignment: iced::alignment::Horizontal::Left,
+                vertical_alignment: iced::alignment::Vertical::Top,
+                shaping: iced::advanced::text::Shaping::Advanced,
+            });
+        });
+    }
+}
+
+impl<'a, M, R> std::convert::From<CropSelector<'a, M>> for iced::Element<'a, M, R>
+where
+    M: 'a,
+    R: renderer::Renderer
+        + iced::advanced::image::Renderer<Handle = iced::widget::image::Handle>
+        + iced::advanced::text::Renderer<Font = iced::Font>
+        + 'a,
+{
+    fn from(value: CropSelector<'a, M>) -> Self {
+        iced::Element::new(value)
+    }
+}
@@ -0,0 +1,176 @@
+const LOG_TARGET: &'static str = "iced_gif_maker::diagnostics";
+
+/// The environment variable that may point to a custom FFmpeg binary.
+const FFMPEG_PATH_ENV: &'static str = "ICED_GIF_MAKER_FFMPEG_PATH";
+const DEFAULT_FFMPEG_PATH: &'static str = "ffmpeg";
+const DEFAULT_FFPROBE_PATH: &'static str = "ffprobe";
+
+/// The FFmpeg filters that every conversion relies on.
+const REQUIRED_FILTERS: [&'static str; 3] = ["palettegen", "paletteuse", "scale"];
+
+/// Where the FFmpeg binary's path comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// Chosen using the settings panel's file picker.
+    Settings,
+    Environment,
+    SystemPath,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Settings => write!(f, "settings"),
+            Source::Environment => write!(f, "{} environment variable", FFMPEG_PATH_ENV),
+            Source::SystemPath => write!(f, "system PATH"),
+        }
+    }
+}
+
+/// The FFmpeg binary used for every FFmpeg call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Binary {
+    pub path: String,
+    pub source: Source,
+}
+
+impl Binary {
+    /// Resolves the binary, from the path chosen in the settings if any,
+    /// from the environment variable if set, or else from the system's PATH.
+    pub fn resolve(custom_path: Option<&std::path::Path>) -> Self {
+        if let Some(path) = custom_path {
+            log::debug!(target: LOG_TARGET, "Custom ffmpeg binary path chosen in the settings: {:?}", path);
+            return Self::custom(path);
+        }
+        match std::env::var(FFMPEG_PATH_ENV)
+            .ok()
+            .filter(|p| !p.is_empty())
+        {
+            Some(path) => {
+                log::debug!(target: LOG_TARGET, "Custom ffmpeg binary path provided through {} environment variable: {}", FFMPEG_PATH_ENV, path);
+                Self {
+                    path,
+                    source: Source::Environment,
+                }
+            }
+            None => Self {
+                path: DEFAULT_FFMPEG_PATH.into(),
+                source: Source::SystemPath,
+            },
+        }
+    }
+
+    pub fn custom(path: &std::path::Path) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            source: Source::Settings,
+        }
+    }
+
+    /// The path to remember between sessions, if it was chosen in the
+    /// settings.
+    pub fn custom_path(&self) -> Option<std::path::PathBuf> {
+        match self.source {
+            Source::Settings => Some(self.path.clone().into()),
+            Source::Environment | Source::SystemPath => None,
+        }
+    }
+
+    /// The `ffprobe` binary shipped alongside FFmpeg, e.g. `ffprobe.exe`
+    /// next to `C:\ffmpeg\bin\ffmpeg.exe`.
+    pub fn ffprobe_path(&self) -> String {
+        let path = std::path::Path::new(&self.path);
+        match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) if name.starts_with("ffmpeg") => path
+                .with_file_name(name.replacen("ffmpeg", "ffprobe", 1))
+                .to_string_lossy()
+                .to_string(),
+            _ => DEFAULT_FFPROBE_PATH.into(),
+        }
+    }
+}
+
+/// What was found out about the FFmpeg binary, by running it.
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub binary: Binary,
+    /// The binary's version line, or why it could not be run.
+    pub version: Result<String, String>,
+    pub missing_filters: Vec<&'static str>,
+}
+
+impl Report {
+    pub fn is_usable(&self) -> bool {
+        self.version.is_ok() && self.missing_filters.is_empty()
+    }
+
+    /// Explains why the binary cannot be used, if it cannot.
+    pub fn problem(&self) -> Option<String> {
+        match &self.version {
+            Err(e) => Some(format!("FFmpeg could not be run ({}).", e)),
+            Ok(_) if !self.missing_filters.is_empty() => Some(format!(
+                "FFmpeg lacks required filters: {}.",
+                self.missing_filters.join(", ")
+            )),
+            Ok(_) => None,
+        }
+    }
+}
+
+/// Runs the binary to find its version and check that it supports the
+/// required filters.
+pub async fn probe(binary: Binary) -> Report {
+    log::debug!(target: LOG_TARGET, "Probing ffmpeg binary: {:?}", binary);
+
+    let version = run(&binary.path, &["-version"]).await.and_then(|stdout| {
+        stdout
+            .lines()
+            .next()
+            .map(|line| line.trim().to_string())
+            .ok_or("it did not report its version".to_string())
+    });
+
+    let missing_filters = match &version {
+        Ok(_) => match run(&binary.path, &["-hide_banner", "-filters"]).await {
+            // NOTE: Filter lines look like ` TSC palettegen  V->V  Find...`,
+            // where the first column holds flags.
+            Ok(stdout) => {
+                let filters: Vec<&str> = stdout
+                    .lines()
+                    .filter_map(|line| line.split_whitespace().nth(1))
+                    .collect();
+                REQUIRED_FILTERS
+                    .into_iter()
+                    .filter(|filter| !filters.contains(filter))
+                    .collect()
+            }
+            Err(e) => {
+                log::warn!(target: LOG_TARGET, "Failed to list ffmpeg filters: {}", e);
+                REQUIRED_FILTERS.to_vec()
+            }
+        },
+        Err(_) => vec![],
+    };
+
+    let report = Report {
+        binary,
+        version,
+        missing_filters,
+    };
+    log::info!(target: LOG_TARGET, "FFmpeg diagnostics: {:?}", report);
+    report
+}
+
+async fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!("it exited with {}", output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into())
+}
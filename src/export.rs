@@ -0,0 +1,227 @@
+use crate::preview;
+
+const LOG_TARGET: &'static str = "iced_gif_maker::export";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// A numbered PNG file per frame, written into a directory.
+    Frames,
+    /// A single PNG holding every frame in a grid, along with a JSON
+    /// manifest of the frames' rectangles and delays.
+    SpriteSheet,
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Frames => write!(f, "PNG frames"),
+            Kind::SpriteSheet => write!(f, "Sprite sheet"),
+        }
+    }
+}
+
+/// Writes the frames as a PNG sequence into `directory`, naming the files
+/// after `name` (e.g. `name_001.png`). Returns the number of written files.
+pub async fn frames(
+    frames: preview::Frames,
+    directory: std::path::PathBuf,
+    name: String,
+) -> Result<usize, String> {
+    spawn(move || {
+        let digits = frames.len().to_string().len().max(3);
+        for (index, frame) in frames.iter().enumerate() {
+            let path = directory.join(format!("{}_{:0digits$}.png", name, index + 1));
+            frame_image(frame)?
+                .save_with_format(&path, image::ImageFormat::Png)
+                .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        }
+        log::debug!(target: LOG_TARGET, "Exported {} frame(s) to {:?}", frames.len(), directory);
+        Ok(frames.len())
+    })
+    .await
+}
+
+/// Writes the frames as a sprite sheet to `path`, and its manifest next to
+/// it (i.e. with a `.json` extension). Returns the manifest's path.
+pub async fn sprite_sheet(
+    frames: preview::Frames,
+    path: std::path::PathBuf,
+) -> Result<std::path::PathBuf, String> {
+    spawn(move || {
+        let layout = Layout::new(frames.len(), frames.width(), frames.height());
+        let (width, height) = layout.size();
+        let mut sheet = image::RgbaImage::new(width, height);
+        for (index, frame) in frames.iter().enumerate() {
+            let (x, y) = layout.position(index);
+            image::imageops::replace(&mut sheet, &frame_image(frame)?, x as i64, y as i64);
+        }
+        sheet
+            .save_with_format(&path, image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+
+        let image_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let delays: Vec<_> = frames.iter().map(|frame| frame.delay).collect();
+        let manifest_path = path.with_extension("json");
+        std::fs::write(&manifest_path, manifest(&image_name, &layout, &delays))
+            .map_err(|e| format!("Failed to write {:?}: {}", manifest_path, e))?;
+
+        log::debug!(target: LOG_TARGET, "Exported a {}x{} sprite sheet to {:?}", layout.columns, layout.rows, path);
+        Ok(manifest_path)
+    })
+    .await
+}
+
+/// How the frames are laid out on a sprite sheet: row by row, in a grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Layout {
+    columns: u32,
+    rows: u32,
+    frame_width: u32,
+    frame_height: u32,
+}
+
+impl Layout {
+    fn new(count: usize, frame_width: u32, frame_height: u32) -> Self {
+        // NOTE: The grid is kept as square as possible, since some
+        // consumers limit textures' dimensions.
+        let columns = (count as f64).sqrt().ceil().max(1.0) as u32;
+        let rows = (count as u32 + columns - 1) / columns;
+        Self {
+            columns,
+            rows,
+            frame_width,
+            frame_height,
+        }
+    }
+
+    /// The sheet's size, in pixels.
+    fn size(&self) -> (u32, u32) {
+        (
+            self.frame_width * self.columns,
+            self.frame_height * self.rows,
+        )
+    }
+
+    /// The top left corner of the frame at `index`, in pixels.
+    fn position(&self, index: usize) -> (u32, u32) {
+        (
+            (index as u32 % self.columns) * self.frame_width,
+            (index as u32 / self.columns) * self.frame_height,
+        )
+    }
+}
+
+/// Returns the JSON manifest describing the sheet, with each frame's
+/// rectangle and delay (in milliseconds).
+fn manifest(image_name: &str, layout: &Layout, delays: &[std::time::Duration]) -> String {
+    let rects: Vec<String> = delays
+        .iter()
+        .enumerate()
+        .map(|(index, delay)| {
+            let (x, y) = layout.position(index);
+            format!(
+                "    {{\"x\": {}, \"y\": {}, \"w\": {}, \"h\": {}, \"delay\": {}}}",
+                x,
+                y,
+                layout.frame_width,
+                layout.frame_height,
+                delay.as_millis()
+            )
+        })
+        .collect();
+    let (width, height) = layout.size();
+    format!(
+        "{{\n  \"image\": {},\n  \"width\": {},\n  \"height\": {},\n  \"frames\": [\n{}\n  ]\n}}\n",
+        json_string(image_name),
+        width,
+        height,
+        rects.join(",\n")
+    )
+}
+
+fn frame_image(frame: &preview::Frame) -> Result<image::RgbaImage, String> {
+    frame
+        .to_image()
+        .ok_or("Failed to export frames: missing frame pixels.".into())
+}
+
+/// Runs the export on a separate thread, since encoding PNGs is slow.
+async fn spawn<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let (tx, rx) = iced::futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(f());
+    });
+    rx.await
+        .map_err(|_| "Export thread stopped unexpectedly.".to_string())?
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lays_frames_out_in_a_square_grid() {
+        let grid = |count| {
+            let layout = Layout::new(count, 10, 5);
+            (layout.columns, layout.rows, layout.size())
+        };
+        assert_eq!(grid(1), (1, 1, (10, 5)));
+        assert_eq!(grid(2), (2, 1, (20, 5)));
+        assert_eq!(grid(5), (3, 2, (30, 10)));
+        assert_eq!(grid(9), (3, 3, (30, 15)));
+    }
+
+    #[test]
+    fn positions_frames_row_by_row() {
+        let layout = Layout::new(5, 10, 5);
+        let positions: Vec<_> = (0..5).map(|index| layout.position(index)).collect();
+        assert_eq!(positions, [(0, 0), (10, 0), (20, 0), (0, 5), (10, 5)]);
+
+        let layout = Layout::new(9, 10, 5);
+        assert_eq!(layout.position(8), (20, 10));
+    }
+
+    #[test]
+    fn writes_the_manifest() {
+        let layout = Layout::new(2, 10, 5);
+        let delays = [
+            std::time::Duration::from_millis(100),
+            std::time::Duration::from_millis(40),
+        ];
+        assert_eq!(
+            manifest("clip_sheet.png", &layout, &delays),
+            "{\n  \"image\": \"clip_sheet.png\",\n  \"width\": 20,\n  \"height\": 5,\n  \"frames\": [\n\
+             \x20   {\"x\": 0, \"y\": 0, \"w\": 10, \"h\": 5, \"delay\": 100},\n\
+             \x20   {\"x\": 10, \"y\": 0, \"w\": 10, \"h\": 5, \"delay\": 40}\n  ]\n}\n"
+        );
+    }
+
+    #[test]
+    fn escapes_json_strings() {
+        assert_eq!(json_string("clip.png"), r#""clip.png""#);
+        assert_eq!(json_string(r#"say "hi".png"#), r#""say \"hi\".png""#);
+        assert_eq!(json_string(r"a\b.png"), r#""a\\b.png""#);
+        assert_eq!(json_string("a\nb\tc\u{1}"), r#""a\u000ab\u0009c\u0001""#);
+        assert_eq!(json_string("é.png"), "\"é.png\"");
+    }
+}
@@ -0,0 +1,249 @@
+use crate::converter;
+use crate::metadata;
+use crate::preview;
+use crate::target_size;
+use crate::worker::JobId;
+
+const LOG_TARGET: &'static str = "iced_gif_maker::job";
+
+/// The largest dimension of the still's copy used for previewing transforms.
+const THUMBNAIL_SIZE: u32 = 160;
+
+#[derive(Debug)]
+pub struct Still {
+    pub handle: iced::widget::image::Handle,
+    pub width: u32,
+    pub height: u32,
+    /// A small copy of the frame, which transforms get applied to.
+    thumbnail: Option<image::RgbaImage>,
+    /// The (cropped) frame as it will be oriented in the output, unless
+    /// untransformed.
+    pub oriented: Option<iced::widget::image::Handle>,
+}
+
+impl Still {
+    pub fn new(png_data: Vec<u8>, width: u32, height: u32) -> Self {
+        let thumbnail = image::load_from_memory_with_format(&png_data, image::ImageFormat::Png)
+            .map(|image| image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgba8())
+            .map_err(|e| log::warn!(target: LOG_TARGET, "Failed to decode still frame: {}", e))
+            .ok();
+        Self {
+            handle: iced::widget::image::Handle::from_memory(png_data),
+            width,
+            height,
+            thumbnail,
+            oriented: None,
+        }
+    }
+
+    fn orient(
+        &self,
+        crop: Option<converter::Crop>,
+        transform: converter::Transform,
+    ) -> Option<iced::widget::image::Handle> {
+        if transform.is_identity() {
+            return None;
+        }
+        let thumbnail = self.thumbnail.as_ref()?;
+
+        let mut image = match crop {
+            Some(crop) => {
+                let scale = thumbnail.width() as f32 / self.width.max(1) as f32;
+                let to_thumbnail = |value: u32| (value as f32 * scale).round() as u32;
+                let x = to_thumbnail(crop.x).min(thumbnail.width() - 1);
+                let y = to_thumbnail(crop.y).min(thumbnail.height() - 1);
+                image::imageops::crop_imm(
+                    thumbnail,
+                    x,
+                    y,
+                    to_thumbnail(crop.width).clamp(1, thumbnail.width() - x),
+                    to_thumbnail(crop.height).clamp(1, thumbnail.height() - y),
+                )
+                .to_image()
+            }
+            None => thumbnail.clone(),
+        };
+        image = match transform.rotation {
+            converter::Rotation::None => image,
+            converter::Rotation::Quarter => image::imageops::rotate90(&image),
+            converter::Rotation::Half => image::imageops::rotate180(&image),
+            converter::Rotation::ThreeQuarters => image::imageops::rotate270(&image),
+        };
+        if transform.flip_horizontal {
+            image::imageops::flip_horizontal_in_place(&mut image);
+        }
+        if transform.flip_vertical {
+            image::imageops::flip_vertical_in_place(&mut image);
+        }
+        Some(iced::widget::image::Handle::from_pixels(
+            image.width(),
+            image.height(),
+            image.into_raw(),
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    /// The video's first frame is being extracted.
+    Loading,
+    /// The video can be previewed and cropped, and is waiting to be converted.
+    Pending,
+    /// The job was sent to the worker, which will run it after the ones
+    /// ahead of it.
+    Queued,
+    Converting,
+    /// The converted GIF is being decoded for previewing.
+    Decoding,
+    /// The edited frames are being encoded into a new GIF.
+    Encoding,
+    Done,
+    Failed(String),
+}
+
+#[derive(Debug)]
+pub struct Job {
+    pub id: JobId,
+    /// The video's path or, for image sequences, their directory's.
+    pub path: std::path::PathBuf,
+    /// The frames, when converting an image sequence rather than a video.
+    pub images: Option<Vec<std::path::PathBuf>>,
+    pub status: Status,
+    pub still: Option<Still>,
+    pub crop: Option<converter::Crop>,
+    pub trim: converter::Trim,
+    pub format: converter::Format,
+    /// The palette settings the job was converted with, which edited frames
+    /// get re-encoded with.
+    pub palette: converter::Palette,
+    pub looping: converter::Loop,
+    pub video_duration: Option<std::time::Duration>,
+    /// The video's properties, as reported by `ffprobe`.
+    pub metadata: Option<metadata::Metadata>,
+    pub progress: Option<f64>,
+    pub image_data: Option<Vec<u8>>,
+    pub player: Option<preview::Player>,
+    /// Whether the player's frames were edited since the output was last
+    /// encoded.
+    pub frames_edited: bool,
+    /// The input's frames, when it is an animated GIF, which get previewed
+    /// before converting it.
+    pub input_player: Option<preview::Player>,
+    /// The target size search, when the job was converted in that mode.
+    pub search: Option<target_size::Search>,
+    /// Cancels the output's GIF frame decoding (when set), while the job is
+    /// `Decoding`.
+    pub decoding: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
+    /// Cancels the animated input's frame decoding (when set), which runs
+    /// independently of the job's status.
+    pub input_decoding: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
+}
+
+impl Job {
+    pub fn new(id: JobId, path: std::path::PathBuf) -> Self {
+        Self {
+            id,
+            path,
+            images: None,
+            status: Status::Loading,
+            still: None,
+            crop: None,
+            trim: Default::default(),
+            format: Default::default(),
+            palette: Default::default(),
+            looping: Default::default(),
+            video_duration: None,
+            metadata: None,
+            progress: None,
+            image_data: None,
+            player: None,
+            frames_edited: false,
+            input_player: None,
+            search: None,
+            decoding: None,
+            input_decoding: None,
+        }
+    }
+
+    pub fn new_image_sequence(
+        id: JobId,
+        directory: std::path::PathBuf,
+        images: Vec<std::path::PathBuf>,
+    ) -> Self {
+        Self {
+            images: Some(images),
+            ..Self::new(id, directory)
+        }
+    }
+
+    /// Refreshes the still's preview of the output's orientation.
+    pub fn orient_still(&mut self, transform: converter::Transform) {
+        let crop = self.crop;
+        if let Some(still) = self.still.as_mut() {
+            still.oriented = still.orient(crop, transform);
+        }
+    }
+
+    /// The player shown for the job: the input's before converting it, and
+    /// the output's once converted.
+    pub fn active_player(&self) -> Option<&preview::Player> {
+        match self.status {
+            Status::Pending => self.input_player.as_ref(),
+            _ => self.player.as_ref(),
+        }
+    }
+
+    pub fn active_player_mut(&mut self) -> Option<&mut preview::Player> {
+        match self.status {
+            Status::Pending => self.input_player.as_mut(),
+            _ => self.player.as_mut(),
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        let name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or(self.path.to_string_lossy().to_string());
+        match self.images.as_ref() {
+            Some(images) => format!("{} ({} images)", name, images.len()),
+            None => name,
+        }
+    }
+
+    /// Whether the job is in the worker's hands, in which case it must be
+    /// cancelled through the worker rather than simply removed.
+    pub fn is_in_worker(&self) -> bool {
+        matches!(self.status, Status::Queued | Status::Converting)
+    }
+
+    pub fn is_working(&self) -> bool {
+        self.is_in_worker() || matches!(self.status, Status::Decoding | Status::Encoding)
+    }
+
+    pub fn status_description(&self) -> String {
+        match &self.status {
+            Status::Loading => "Loading...".into(),
+            Status::Pending => "Ready to convert".into(),
+            Status::Queued => "Queued".into(),
+            Status::Converting => {
+                let attempt = match self.search.as_ref().map(|search| search.attempts().len()) {
+                    Some(count) if count > 1 => format!(" (attempt {})", count),
+                    _ => "".into(),
+                };
+                match self.progress {
+                    Some(progress) => {
+                        format!("Converting{} - {:.0}%", attempt, progress * 100.0)
+                    }
+                    None => format!("Converting{}...", attempt),
+                }
+            }
+            Status::Decoding => "Loading preview...".into(),
+            Status::Encoding => "Encoding edits...".into(),
+            Status::Done if self.frames_edited => "Done (edits not applied)".into(),
+            Status::Done => "Done".into(),
+            Status::Failed(_) => "Failed".into(),
+        }
+    }
+}
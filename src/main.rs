@@ -1,6 +1,17 @@
 use iced::Application as _;
 
+mod cli;
+mod converter;
+mod crop_selector;
+mod diagnostics;
+mod export;
+mod job;
+mod metadata;
+mod overlay;
+mod preferences;
+mod preview;
 mod styling;
+mod target_size;
 mod worker;
 
 #[cfg(windows)]
@@ -18,10 +29,18 @@ const CONTENT_FONT_SIZE: u16 = 16;
 const FOOTER_FONT_SIZE: u16 = 12;
 const LOADING_INDICATOR_SIZE: f32 = 120.0;
 const LOADING_INDICATOR_SPEED_MS: u64 = 100;
+const SIDEBAR_WIDTH: f32 = 200.0;
+const TOOLBAR_LABEL_WIDTH: f32 = 60.0;
+const FILMSTRIP_THUMBNAIL_HEIGHT: f32 = 60.0;
+const ORIENTATION_PREVIEW_HEIGHT: f32 = 80.0;
 
-const ALLOWED_VIDEO_TYPES: [&'static str; 11] = [
-    "mp4", "mov", "wmv", "avi", "avchd", "flv", "f4v", "swf", "mkv", "webm", "html5",
+const ALLOWED_VIDEO_TYPES: [&'static str; 12] = [
+    "mp4", "mov", "wmv", "avi", "avchd", "flv", "f4v", "swf", "mkv", "webm", "html5", "gif",
 ];
+const ALLOWED_IMAGE_TYPES: [&'static str; 3] = ["png", "jpg", "jpeg"];
+/// Image files dropped together arrive as separate events, so the ones
+/// dropped within this delay are grouped into the same sequence.
+const DROP_GROUPING_MS: u64 = 500;
 
 const LOG_TARGET: &'static str = "iced_gif_maker::main";
 
@@ -32,40 +51,178 @@ const SPACING_NORMAL: u16 = 10;
 const SPACING_LARGE: u16 = 20;
 
 const DEFAULT_GIF_WIDTH: u16 = 480;
+const DEFAULT_GIF_HEIGHT: u16 = 360;
+const DEFAULT_SIZE_PERCENT: u16 = 50;
+const DEFAULT_GIF_FPS: u8 = 10;
+const MAX_GIF_FPS: u8 = 50;
+const DEFAULT_FRAME_DELAY_MS: u16 = 100;
+
+const DEFAULT_WINDOW_SIZE: (u32, u32) = (700, 500);
+const DEFAULT_WINDOW_POSITION: (i32, i32) = (100, 800);
 
 fn main() -> iced::Result {
     #[cfg(feature = "logging")]
     {
         if std::env::var("RUST_LOG").ok().is_none() {
-            // std::env::set_var("RUST_LOG", "iced_gif_maker=debug");
-            std::env::set_var("RUST_LOG", "iced_gif_maker=debug,ffmpeg_gif_maker=info");
+            std::env::set_var("RUST_LOG", "iced_gif_maker=debug");
         }
         env_logger::init();
     }
 
+    let args = match cli::Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(cli::EXIT_CODE_USAGE);
+        }
+    };
+
+    if args.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    if args.is_headless() {
+        std::process::exit(cli::run_headless(args));
+    }
+
+    // NOTE: The preferences are loaded here, rather than by the application,
+    // since the window's size and position must be known before opening it.
+    // They are then passed along as flags.
+    let preferences = preferences::Preferences::load();
+
     MyApp::run(iced::Settings {
         window: iced::window::Settings {
-            size: (700, 500),
+            size: preferences.window_size,
             min_size: Some((400, 285)),
-            position: iced::window::Position::Specific(100, 800),
+            position: match preferences.window_position {
+                Some((x, y)) => iced::window::Position::Specific(x, y),
+                None => iced::window::Position::Default,
+            },
+            // NOTE: The close request is handled by the application, so that
+            // preferences can be saved before exiting.
+            exit_on_close_request: false,
             ..Default::default()
         },
+        flags: Flags { args, preferences },
         ..Default::default()
     })
 }
 
+#[derive(Debug, Default)]
+struct Flags {
+    args: cli::Args,
+    preferences: preferences::Preferences,
+}
+
+/// Whether the input is an animated GIF, which gets decoded for previewing
+/// (on top of being converted like videos).
+fn is_gif_file(path: &std::path::Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"))
+}
+
+fn is_image_file(path: &std::path::Path) -> bool {
+    path.extension().is_some_and(|extension| {
+        let extension = extension.to_string_lossy().to_lowercase();
+        ALLOWED_IMAGE_TYPES.contains(&extension.as_str())
+    })
+}
+
+/// Returns the image files found in the directory, sorted by name.
+fn read_image_directory(directory: &std::path::Path) -> std::io::Result<Vec<std::path::PathBuf>> {
+    let mut images = vec![];
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.is_file() && is_image_file(&path) {
+            images.push(path);
+        }
+    }
+    sort_image_paths(&mut images);
+    Ok(images)
+}
+
+/// Sorts paths by file name, comparing digit runs by their numeric value,
+/// so that e.g. `frame_2.png` comes before `frame_10.png`.
+fn sort_image_paths(paths: &mut [std::path::PathBuf]) {
+    paths.sort_by_cached_key(|path| {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // NOTE: Digit runs are padded with zeros, so that comparing them as
+        // text compares their values.
+        let mut key = String::new();
+        let mut digits = String::new();
+        for c in name.chars().chain(std::iter::once('\0')) {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                continue;
+            }
+            if !digits.is_empty() {
+                key.push_str(&format!("{:0>20}", digits));
+                digits.clear();
+            }
+            key.push(c);
+        }
+        key
+    });
+}
+
 #[derive(Debug)]
 struct MyApp {
     loaded_resources_count: usize,
-    error_message: Option<String>,
-    progress: Option<f64>,
-    video_duration: Option<std::time::Duration>,
-    image_data: Option<Vec<u8>>,
     tx: Option<iced::futures::channel::mpsc::Sender<worker::Input>>,
-    frames: Option<iced_gif::gif::Frames>,
-    video_path: Option<std::path::PathBuf>,
+    jobs: Vec<job::Job>,
+    selected_job: Option<worker::JobId>,
+    next_job_id: worker::JobId,
+    size_mode: converter::SizeMode,
     gif_width: Option<u16>,
-    idle: bool,
+    gif_height: Option<u16>,
+    size_percent: Option<u16>,
+    scaling: converter::ScaleAlgorithm,
+    transform: converter::Transform,
+    gif_fps: Option<u8>,
+    frame_delay: Option<u16>,
+    /// The trim inputs' text, since they accept fractional seconds.
+    trim_start: String,
+    trim_end: String,
+    max_colors: Option<u16>,
+    dither: converter::Dither,
+    bayer_scale: Option<u8>,
+    stats_mode: converter::StatsMode,
+    show_settings: bool,
+    show_frame_editor: bool,
+    show_diagnostics: bool,
+    ffmpeg: diagnostics::Binary,
+    /// The FFmpeg binary's diagnostics, once it has been probed.
+    diagnostics: Option<diagnostics::Report>,
+    format: converter::Format,
+    /// In KiB.
+    target_size: Option<u32>,
+    plays: Option<u16>,
+    overlay_text: String,
+    overlay_position: overlay::Position,
+    overlay_font_size: Option<u16>,
+    overlay_color: overlay::Color,
+    overlay_background: bool,
+    speed: converter::Speed,
+    direction: converter::Direction,
+    open_directory: Option<std::path::PathBuf>,
+    save_directory: Option<std::path::PathBuf>,
+    window_size: (u32, u32),
+    window_position: Option<(i32, i32)>,
+    notifications: Vec<Notification>,
+    next_notification_id: u64,
+    /// The image sequence job that the last dropped image was added to.
+    last_image_drop: Option<(worker::JobId, std::time::Instant)>,
+}
+
+/// An error message displayed in a banner until it gets dismissed.
+#[derive(Debug)]
+struct Notification {
+    id: u64,
+    message: String,
 }
 
 #[derive(Debug, Clone)]
@@ -74,29 +231,117 @@ enum MyMessage {
     ConvertMessageSentToWorker,
     CancelMessageSentToWorker,
     WorkerEvent(worker::Event),
-    GifFramesLoaded(Result<iced_gif::gif::Frames, iced_gif::gif::Error>),
+    GifFramesLoaded(worker::JobId, Result<preview::Frames, preview::Error>),
+    InputFramesLoaded(worker::JobId, Result<preview::Frames, preview::Error>),
+    EditAgain,
+    ToggleFrameEditor,
+    DeleteFrame,
+    DuplicateFrame,
+    EditFrameDelay(Option<u32>),
+    ApplyFrameEdits,
+    FramesEncoded(worker::JobId, Result<Vec<u8>, preview::Error>),
+    PlayPause,
+    StepFrame(isize),
+    SeekFrame(u32),
+    PreviewTick,
     Event(iced::Event),
     Clear,
     SelectFile,
-    FileSelected(Option<std::path::PathBuf>),
+    SelectFolder,
+    FrameDelay(Option<u16>),
+    FilesSelected(Vec<std::path::PathBuf>),
+    StillLoaded(worker::JobId, Result<converter::Frame, converter::Error>),
+    SelectJob(worker::JobId),
+    RemoveJob(worker::JobId),
+    Crop(Option<converter::Crop>),
+    Convert,
+    ToggleSettings,
+    MaxColors(Option<u16>),
+    Dither(converter::Dither),
+    BayerScale(Option<u8>),
+    StatsMode(converter::StatsMode),
+    Format(converter::Format),
+    TargetSize(Option<u32>),
+    Plays(Option<u16>),
+    OverlayText(String),
+    OverlayPosition(overlay::Position),
+    OverlayFontSize(Option<u16>),
+    OverlayColor(overlay::Color),
+    OverlayBackground(bool),
+    Speed(converter::Speed),
+    Direction(converter::Direction),
     Width(Option<u16>),
-    SaveResult(Result<bool, String>),
+    Height(Option<u16>),
+    SizePercent(Option<u16>),
+    SizeMode(converter::SizeMode),
+    Scaling(converter::ScaleAlgorithm),
+    Rotate,
+    FlipHorizontal,
+    FlipVertical,
+    Fps(Option<u8>),
+    TrimStart(String),
+    TrimEnd(String),
+    SaveResult(Result<Option<std::path::PathBuf>, String>),
+    DismissNotification(u64),
     Save,
+    Export(export::Kind),
+    ToggleDiagnostics,
+    ProbeFfmpeg,
+    MetadataLoaded(worker::JobId, Result<metadata::Metadata, String>),
+    ApplySuggestedSettings,
+    FfmpegProbed(diagnostics::Report),
+    SelectFfmpeg,
+    FfmpegSelected(Option<std::path::PathBuf>),
+    FfmpegValidated(diagnostics::Report),
+    ResetFfmpeg,
+    PreferencesSaved(Result<(), String>),
+    ExportResult(Result<Option<String>, String>),
 }
 
 impl Default for MyApp {
     fn default() -> Self {
         Self {
             loaded_resources_count: 0,
-            progress: None,
-            image_data: None,
-            video_duration: None,
             tx: None,
-            frames: None,
-            video_path: None,
+            jobs: vec![],
+            selected_job: None,
+            next_job_id: 0,
+            size_mode: Default::default(),
             gif_width: Some(DEFAULT_GIF_WIDTH),
-            error_message: None,
-            idle: true,
+            gif_height: Some(DEFAULT_GIF_HEIGHT),
+            size_percent: Some(DEFAULT_SIZE_PERCENT),
+            scaling: Default::default(),
+            transform: Default::default(),
+            gif_fps: Some(DEFAULT_GIF_FPS),
+            frame_delay: Some(DEFAULT_FRAME_DELAY_MS),
+            trim_start: String::new(),
+            trim_end: String::new(),
+            max_colors: Some(converter::Palette::default().max_colors),
+            dither: Default::default(),
+            bayer_scale: Some(converter::Palette::default().bayer_scale),
+            stats_mode: Default::default(),
+            show_settings: false,
+            show_frame_editor: false,
+            show_diagnostics: false,
+            ffmpeg: diagnostics::Binary::resolve(None),
+            diagnostics: None,
+            format: Default::default(),
+            target_size: None,
+            plays: None,
+            overlay_text: String::new(),
+            overlay_position: Default::default(),
+            overlay_font_size: Some(overlay::Overlay::DEFAULT_FONT_SIZE),
+            overlay_color: Default::default(),
+            overlay_background: true,
+            speed: Default::default(),
+            direction: Default::default(),
+            open_directory: None,
+            save_directory: None,
+            window_size: DEFAULT_WINDOW_SIZE,
+            window_position: Some(DEFAULT_WINDOW_POSITION),
+            notifications: vec![],
+            next_notification_id: 0,
+            last_image_drop: None,
         }
     }
 }
@@ -122,134 +367,850 @@ impl MyApp {
         self.loaded_resources_count == 2
     }
 
-    fn clear_all(&mut self) {
-        self.frames = None;
-        self.image_data = None;
-        self.progress = None;
-        self.video_duration = None;
-        self.video_path = None;
-        self.error_message = None;
-        self.idle = true;
+    fn job(&self, id: worker::JobId) -> Option<&job::Job> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+
+    fn job_mut(&mut self, id: worker::JobId) -> Option<&mut job::Job> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+
+    fn selected_job(&self) -> Option<&job::Job> {
+        self.selected_job.and_then(|id| self.job(id))
+    }
+
+    fn selected_player_mut(&mut self) -> Option<&mut preview::Player> {
+        let id = self.selected_job?;
+        self.job_mut(id).and_then(|job| job.active_player_mut())
+    }
+
+    /// Adds a job for each video and directory, and a single image sequence
+    /// job for all the image files.
+    fn add_paths(&mut self, paths: Vec<std::path::PathBuf>) -> iced::Command<MyMessage> {
+        let (mut images, others): (Vec<_>, Vec<_>) =
+            paths.into_iter().partition(|path| is_image_file(path));
+
+        let mut commands: Vec<iced::Command<MyMessage>> = vec![];
+        for path in others {
+            if path.is_dir() {
+                commands.push(self.add_directory(path));
+            } else {
+                commands.push(self.add_job(path, None));
+            }
+        }
+        if let Some(directory) = images.first().and_then(|path| path.parent()) {
+            let directory = directory.to_path_buf();
+            sort_image_paths(&mut images);
+            commands.push(self.add_job(directory, Some(images)));
+        }
+        iced::Command::batch(commands)
+    }
+
+    fn add_directory(&mut self, directory: std::path::PathBuf) -> iced::Command<MyMessage> {
+        match read_image_directory(&directory) {
+            Ok(images) if !images.is_empty() => self.add_job(directory, Some(images)),
+            Ok(_) => {
+                self.notify(format!("No PNG or JPEG images found in {:?}.", directory));
+                iced::Command::none()
+            }
+            Err(e) => {
+                self.notify(format!("Failed to read {:?}: {}", directory, e));
+                iced::Command::none()
+            }
+        }
+    }
+
+    /// Adds a dropped image to the sequence of the images dropped along with
+    /// it, or starts a new sequence.
+    fn add_dropped_image(&mut self, path: std::path::PathBuf) -> iced::Command<MyMessage> {
+        let now = std::time::Instant::now();
+        let grouping_delay = std::time::Duration::from_millis(DROP_GROUPING_MS);
+        let sequence = self
+            .last_image_drop
+            .filter(|(_, dropped_at)| now.duration_since(*dropped_at) < grouping_delay)
+            .and_then(|(id, _)| self.job_mut(id))
+            .filter(|job| matches!(job.status, job::Status::Loading | job::Status::Pending))
+            .and_then(|job| job.images.as_mut().map(|images| (job.id, images)));
+
+        if let Some((id, images)) = sequence {
+            log::debug!(target: LOG_TARGET, "Adding dropped image to job {}.", id);
+            images.push(path);
+            sort_image_paths(images);
+            self.last_image_drop = Some((id, now));
+            self.update_sequence_durations();
+            return iced::Command::none();
+        }
+
+        let id = self.next_job_id;
+        let command = self.add_paths(vec![path]);
+        self.last_image_drop = Some((id, now));
+        command
+    }
+
+    /// Adds a job for the video (or the image sequence) and starts loading
+    /// its preview.
+    fn add_job(
+        &mut self,
+        path: std::path::PathBuf,
+        images: Option<Vec<std::path::PathBuf>>,
+    ) -> iced::Command<MyMessage> {
+        let id = self.next_job_id;
+        self.next_job_id += 1;
+        log::debug!(target: LOG_TARGET, "Adding job {} for video: {:?}", id, path);
+
+        // NOTE: The new job only steals the selection if the selected job is
+        // not being set up, so that dropping many files keeps the first one
+        // selected.
+        let keep_selection = self
+            .selected_job()
+            .is_some_and(|job| matches!(job.status, job::Status::Loading | job::Status::Pending));
+        if !keep_selection {
+            self.selected_job = Some(id);
+        }
+
+        // NOTE: Image sequences are previewed using their first image.
+        let preview_path = images
+            .as_ref()
+            .and_then(|images| images.first())
+            .unwrap_or(&path);
+        let f = converter::extract_frame(
+            Some(self.ffmpeg.path.clone()),
+            preview_path.to_string_lossy().to_string(),
+        );
+        let mut commands = vec![iced::Command::perform(f, move |result| {
+            MyMessage::StillLoaded(id, result)
+        })];
+
+        // NOTE: The probe only informs users, so the job does not wait for
+        // it (nor fails along with it).
+        if images.is_none() {
+            let f = metadata::probe(
+                self.ffmpeg.ffprobe_path(),
+                path.to_string_lossy().to_string(),
+            );
+            commands.push(iced::Command::perform(f, move |result| {
+                MyMessage::MetadataLoaded(id, result)
+            }));
+        }
+
+        // NOTE: Animated GIFs are also decoded, so that they can be played
+        // while setting up their conversion.
+        let mut decoding = None;
+        if images.is_none() && is_gif_file(&path) {
+            let cancel: std::sync::Arc<std::sync::atomic::AtomicBool> = Default::default();
+            decoding = Some(cancel.clone());
+            commands.push(iced::Command::perform(
+                preview::Frames::decode_file(path.clone(), cancel),
+                move |result| MyMessage::InputFramesLoaded(id, result),
+            ));
+        }
+
+        let mut job = match images {
+            Some(images) => job::Job::new_image_sequence(id, path, images),
+            None => job::Job::new(id, path),
+        };
+        job.input_decoding = decoding;
+        self.jobs.push(job);
+        self.update_sequence_durations();
+        iced::Command::batch(commands)
+    }
+
+    /// Applies an edit to the selected job's output frames.
+    fn edit_frames(&mut self, edit: impl FnOnce(&mut preview::Player)) {
+        let Some(job) = self
+            .selected_job
+            .and_then(|id| self.job_mut(id))
+            .filter(|job| job.status == job::Status::Done)
+        else {
+            return;
+        };
+        let Some(player) = job.player.as_mut() else {
+            return;
+        };
+        edit(player);
+        job.frames_edited = true;
+    }
+
+    /// Encodes the selected job's edited frames, which then replace its
+    /// output.
+    fn apply_frame_edits(&mut self) -> iced::Command<MyMessage> {
+        let ffmpeg_path = self.ffmpeg.path.clone();
+        let Some(job) = self
+            .selected_job
+            .and_then(|id| self.job_mut(id))
+            .filter(|job| job.status == job::Status::Done && job.frames_edited)
+        else {
+            return iced::Command::none();
+        };
+        let Some(frames) = job.player.as_ref().map(|player| player.frames().clone()) else {
+            return iced::Command::none();
+        };
+        let id = job.id;
+        log::debug!(target: LOG_TARGET, "Encoding edited frames of job {}...", id);
+        job.status = job::Status::Encoding;
+        iced::Command::perform(
+            frames.encode(ffmpeg_path, job.palette, job.looping),
+            move |result| MyMessage::FramesEncoded(id, result),
+        )
+    }
+
+    /// Sends a converted job back to the pending state, so that it can be
+    /// converted again using different settings.
+    fn edit_again(&mut self, id: worker::JobId) {
+        let Some(job) = self
+            .job_mut(id)
+            .filter(|job| job.status == job::Status::Done)
+        else {
+            return;
+        };
+        log::debug!(target: LOG_TARGET, "Editing job {} again.", id);
+        job.status = job::Status::Pending;
+        job.progress = None;
+        job.image_data = None;
+        job.player = None;
+        job.frames_edited = false;
+        job.search = None;
+        self.update_sequence_durations();
+    }
+
+    /// Updates the duration of the image sequences that have not been sent
+    /// to the worker yet, since it depends on the frame delay.
+    fn update_sequence_durations(&mut self) {
+        let frame_delay = self.frame_delay();
+        for job in self.jobs.iter_mut() {
+            if !matches!(job.status, job::Status::Loading | job::Status::Pending) {
+                continue;
+            }
+            if let Some(images) = job.images.as_ref() {
+                job.video_duration = Some(frame_delay * images.len() as u32);
+            }
+        }
+    }
+
+    /// Removes a job from the list. Jobs that are in the worker's hands get
+    /// cancelled first, and are only removed once the worker confirms it.
+    fn remove_job(&mut self, id: worker::JobId) -> iced::Command<MyMessage> {
+        let Some(job) = self.job(id) else {
+            log::debug!(target: LOG_TARGET, "Nothing to remove.");
+            return iced::Command::none();
+        };
+
+        if !job.is_in_worker() {
+            log::info!(target: LOG_TARGET, "Removing job {}.", id);
+            self.discard_job(id);
+            return iced::Command::none();
+        }
+
+        let Some(tx) = self.tx.as_ref() else {
+            log::debug!(target: LOG_TARGET, "Cannot cancel job because worker not ready.");
+            return iced::Command::none();
+        };
+
+        log::info!(target: LOG_TARGET, "Cancelling job {}.", id);
+        use iced::futures::sink::SinkExt;
+        let mut tx = tx.clone();
+        let f = async move {
+            let input = worker::Input::Command(id, converter::Command::Cancel);
+            tx.send(input).await
+        };
+        // Should I be ignoring `send` errors here?
+        log::debug!(target: LOG_TARGET, "Dispatching command to send cancellation request to worker...");
+        iced::Command::perform(f, |_| MyMessage::CancelMessageSentToWorker)
+    }
+
+    /// Marks the job as failed and reports the error in a notification.
+    fn fail_job(&mut self, id: worker::JobId, error_message: String) {
+        let Some(job) = self.job_mut(id) else {
+            return;
+        };
+        let notification = format!("Failed to convert {:?}: {}", job.file_name(), error_message);
+        job.status = job::Status::Failed(error_message);
+        self.notify(notification);
+    }
+
+    fn notify(&mut self, message: String) {
+        let id = self.next_notification_id;
+        self.next_notification_id += 1;
+        self.notifications.push(Notification { id, message });
+    }
+
+    /// Completes a job whose conversion succeeded, decoding its output for
+    /// previewing when it is a GIF.
+    fn finish_job(&mut self, id: worker::JobId) -> iced::Command<MyMessage> {
+        let Some(job) = self.job_mut(id) else {
+            return iced::Command::none();
+        };
+        let Some(image_data) = job.image_data.as_ref() else {
+            return iced::Command::none();
+        };
+        if job.format != converter::Format::Gif {
+            log::debug!(target: LOG_TARGET, "Output is not a GIF, so falling back to a static preview.");
+            job.status = job::Status::Done;
+            return iced::Command::none();
+        }
+        let data = image_data.clone();
+        let cancel: std::sync::Arc<std::sync::atomic::AtomicBool> = Default::default();
+        job.status = job::Status::Decoding;
+        job.decoding = Some(cancel.clone());
+        log::debug!(target: LOG_TARGET, "Returning command that will initiate the GIF processing...");
+        iced::Command::perform(preview::Frames::decode(data, cancel), move |result| {
+            MyMessage::GifFramesLoaded(id, result)
+        })
+    }
+
+    fn discard_job(&mut self, id: worker::JobId) {
+        let Some(index) = self.jobs.iter().position(|job| job.id == id) else {
+            return;
+        };
+        let job = self.jobs.remove(index);
+
+        // NOTE: Stale results are also discarded by job identity, but there
+        // is no point in finishing the work for a job that no longer exists.
+        for cancel in [job.decoding, job.input_decoding].into_iter().flatten() {
+            log::debug!(target: LOG_TARGET, "Cancelling GIF decoding for job {}.", id);
+            cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+
+        if self.selected_job == Some(id) {
+            self.selected_job = self.jobs.get(index).or(self.jobs.last()).map(|job| job.id);
+        }
     }
 
     fn select_file(&mut self) -> iced::Command<MyMessage> {
         log::debug!(target: LOG_TARGET, "Presenting video file picker...");
+        let open_directory = self.open_directory.clone();
         iced::Command::perform(
             async {
-                let file = rfd::AsyncFileDialog::new()
+                let mut dialog = rfd::AsyncFileDialog::new()
                     .add_filter("video", &ALLOWED_VIDEO_TYPES)
-                    .pick_file()
-                    .await;
+                    .add_filter("image", &ALLOWED_IMAGE_TYPES);
+                if let Some(directory) = open_directory {
+                    dialog = dialog.set_directory(directory);
+                }
+                let files = dialog.pick_files().await;
+                files
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|handle| handle.path().to_path_buf())
+                    .collect()
+            },
+            MyMessage::FilesSelected,
+        )
+    }
+
+    fn select_folder(&mut self) -> iced::Command<MyMessage> {
+        log::debug!(target: LOG_TARGET, "Presenting folder picker...");
+        let open_directory = self.open_directory.clone();
+        iced::Command::perform(
+            async {
+                let mut dialog = rfd::AsyncFileDialog::new();
+                if let Some(directory) = open_directory {
+                    dialog = dialog.set_directory(directory);
+                }
+                let folder = dialog.pick_folder().await;
+                folder
+                    .map(|handle| handle.path().to_path_buf())
+                    .into_iter()
+                    .collect()
+            },
+            MyMessage::FilesSelected,
+        )
+    }
+
+    /// Whether FFmpeg can be used. It is assumed to be, until probed.
+    fn is_ffmpeg_usable(&self) -> bool {
+        self.diagnostics
+            .as_ref()
+            .map_or(true, diagnostics::Report::is_usable)
+    }
+
+    fn probe_ffmpeg(&mut self) -> iced::Command<MyMessage> {
+        self.diagnostics = None;
+        iced::Command::perform(
+            diagnostics::probe(self.ffmpeg.clone()),
+            MyMessage::FfmpegProbed,
+        )
+    }
+
+    fn select_ffmpeg(&mut self) -> iced::Command<MyMessage> {
+        log::debug!(target: LOG_TARGET, "Presenting file picker (for the ffmpeg binary)...");
+        let directory = std::path::Path::new(&self.ffmpeg.path)
+            .parent()
+            .filter(|directory| directory.is_dir())
+            .map(|directory| directory.to_path_buf());
+        iced::Command::perform(
+            async {
+                let mut dialog = rfd::AsyncFileDialog::new();
+                if let Some(directory) = directory {
+                    dialog = dialog.set_directory(directory);
+                }
+                let file = dialog.pick_file().await;
                 file.map(|handle| handle.path().to_path_buf())
             },
-            MyMessage::FileSelected,
+            MyMessage::FfmpegSelected,
         )
     }
 
-    fn new_task(&mut self, path: std::path::PathBuf) -> iced::Command<MyMessage> {
-        log::debug!(target: LOG_TARGET, "New task requested...");
+    fn width(&self) -> u16 {
+        self.gif_width
+            .filter(|&width| width > 0)
+            .unwrap_or(DEFAULT_GIF_WIDTH)
+    }
 
-        use iced::futures::sink::SinkExt;
+    fn height(&self) -> u16 {
+        self.gif_height
+            .filter(|&height| height > 0)
+            .unwrap_or(DEFAULT_GIF_HEIGHT)
+    }
 
-        let Some(tx) = self.tx.as_ref() else {
-            log::debug!(target: LOG_TARGET, "Task ignored because worker not ready.");
-            return iced::Command::none();
+    fn set_transform(&mut self, transform: converter::Transform) {
+        log::debug!(target: LOG_TARGET, "Transform changed: {}", transform);
+        self.transform = transform;
+        for job in self.jobs.iter_mut() {
+            job.orient_still(transform);
+        }
+    }
+
+    fn size(&self) -> converter::Size {
+        match self.size_mode {
+            converter::SizeMode::Width => converter::Size::Width(self.width()),
+            converter::SizeMode::Height => converter::Size::Height(self.height()),
+            converter::SizeMode::Fit => converter::Size::Fit(self.width(), self.height()),
+            converter::SizeMode::Pad => converter::Size::Pad(self.width(), self.height()),
+            converter::SizeMode::Fill => converter::Size::Fill(self.width(), self.height()),
+            converter::SizeMode::Percent => converter::Size::Percent(
+                self.size_percent
+                    .filter(|&percent| percent > 0)
+                    .unwrap_or(DEFAULT_SIZE_PERCENT),
+            ),
+        }
+    }
+
+    fn frame_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.frame_delay
+                .filter(|&delay| delay > 0)
+                .unwrap_or(DEFAULT_FRAME_DELAY_MS) as u64,
+        )
+    }
+
+    fn fps(&self) -> u8 {
+        self.gif_fps
+            .unwrap_or(DEFAULT_GIF_FPS)
+            .clamp(1, MAX_GIF_FPS)
+    }
+
+    fn looping(&self) -> converter::Loop {
+        converter::Loop::from_plays(self.plays)
+    }
+
+    /// The caption to burn into the output, unless its text is empty.
+    fn overlay(&self) -> Option<overlay::Overlay> {
+        let text = self.overlay_text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        Some(overlay::Overlay {
+            text: text.into(),
+            position: self.overlay_position,
+            font_size: self
+                .overlay_font_size
+                .filter(|&size| size > 0)
+                .unwrap_or(overlay::Overlay::DEFAULT_FONT_SIZE),
+            color: self.overlay_color,
+            background: self.overlay_background,
+        })
+    }
+
+    /// The target size's budget, in bytes.
+    fn target_size_budget(&self) -> Option<usize> {
+        target_size::budget_from_kib(self.target_size)
+    }
+
+    fn settings(&self, job: &job::Job) -> converter::Settings {
+        let input = match job.images.as_ref() {
+            Some(images) => converter::Input::Images(converter::ImageSequence {
+                paths: images.clone(),
+                frame_delay: self.frame_delay(),
+                frame_delays: None,
+            }),
+            None => converter::Input::Video(job.path.to_string_lossy().to_string()),
         };
-        let mut tx = tx.clone();
+        converter::Settings::new(input, self.width(), self.fps())
+            .size(self.size())
+            .scaling(self.scaling)
+            .transform(self.transform)
+            .trim(self.trim().unwrap_or_default())
+            .crop(job.crop)
+            .palette(self.palette())
+            .format(self.format)
+            .speed(self.speed)
+            .direction(self.direction)
+            .looping(self.looping())
+            .overlay(self.overlay())
+            .ffmpeg_path(self.ffmpeg.path.clone())
+    }
 
-        if !self.idle {
-            log::debug!(target: LOG_TARGET, "Task ignored because one is already ongoing.");
+    /// Sends every pending job to the worker, using the current settings.
+    fn new_tasks(&mut self) -> iced::Command<MyMessage> {
+        log::debug!(target: LOG_TARGET, "New tasks requested...");
+
+        if self.tx.is_none() {
+            log::debug!(target: LOG_TARGET, "Tasks ignored because worker not ready.");
             return iced::Command::none();
         }
 
-        self.clear_all();
+        let trim = match self.trim() {
+            Ok(trim) => trim,
+            Err(e) => {
+                log::debug!(target: LOG_TARGET, "Tasks ignored because the trim range is invalid: {}", e);
+                return iced::Command::none();
+            }
+        };
 
-        self.idle = false;
-        self.video_path = Some(path.clone());
+        let ids: Vec<worker::JobId> = self
+            .jobs
+            .iter()
+            .filter(|job| job.status == job::Status::Pending)
+            .map(|job| job.id)
+            .collect();
 
-        let settings = {
-            let settings = ffmpeg_gif_maker::Settings::with_standard_fps(
-                path.to_string_lossy().to_string(),
-                self.gif_width.unwrap_or(DEFAULT_GIF_WIDTH),
-            );
-            if let Some(ffmpeg_path) = std::env::var("ICED_GIF_MAKER_FFMPEG_PATH").ok() {
-                log::debug!(target: LOG_TARGET, "Custom ffmpeg binary path provided through ICED_GIF_MAKER_FFMPEG_PATH environment variable: {}", ffmpeg_path);
-                settings.ffmpeg_path(ffmpeg_path)
-            } else {
-                settings
-            }
+        let (size, fps, palette, format) = (self.size(), self.fps(), self.palette(), self.format);
+        let looping = self.looping();
+        let budget = self.target_size_budget();
+        let mut inputs = vec![];
+        for id in ids {
+            let Some(settings) = self.job(id).map(|job| self.settings(job)) else {
+                continue;
+            };
+            let Some(job) = self.job_mut(id) else {
+                continue;
+            };
+            job.status = job::Status::Queued;
+            job.trim = trim;
+            job.format = format;
+            job.palette = palette;
+            job.looping = looping;
+            job.search = budget.map(|budget| {
+                target_size::Search::new(budget, settings.clone(), size, fps, palette)
+            });
+            let settings = match job.search.as_mut() {
+                Some(search) => search.start(),
+                None => settings,
+            };
+            inputs.push(worker::Input::NewTask(id, settings));
+        }
+
+        self.send_inputs(inputs)
+    }
+
+    fn send_inputs(&self, inputs: Vec<worker::Input>) -> iced::Command<MyMessage> {
+        use iced::futures::sink::SinkExt;
+
+        let Some(tx) = self.tx.as_ref() else {
+            log::debug!(target: LOG_TARGET, "Inputs ignored because worker not ready.");
+            return iced::Command::none();
         };
+        let mut tx = tx.clone();
 
-        log::debug!(target: LOG_TARGET, "Sending new task to worker...");
+        log::debug!(target: LOG_TARGET, "Sending {} new task(s) to worker...", inputs.len());
         iced::Command::perform(
-            async move { tx.send(worker::Input::NewTask(settings)).await },
+            async move {
+                for input in inputs {
+                    tx.send(input).await?;
+                }
+                Ok::<(), iced::futures::channel::mpsc::SendError>(())
+            },
             |_| MyMessage::ConvertMessageSentToWorker,
         )
     }
 
-    fn is_working(&self) -> bool {
-        self.video_path.is_some() && self.frames.is_none() && self.error_message.is_none()
+    /// The trim range, or why the trim inputs are invalid.
+    fn trim(&self) -> Result<converter::Trim, String> {
+        let parse = |value: &str, name: &str| {
+            if value.trim().is_empty() {
+                return Ok(None);
+            }
+            converter::Trim::parse_time(value).map(Some).ok_or(format!(
+                "The trim {} is not a valid time: {:?}.",
+                name, value
+            ))
+        };
+        let trim = converter::Trim {
+            start: parse(&self.trim_start, "start")?,
+            end: parse(&self.trim_end, "end")?,
+        };
+        if !trim.is_valid() {
+            return Err("The trim start must come before the trim end.".into());
+        }
+        Ok(trim)
+    }
+
+    fn trim_description(&self, job: &job::Job) -> String {
+        let trim = job.trim;
+        let start = format_duration(trim.start.unwrap_or_default());
+        let end = match (trim.end, job.video_duration) {
+            (Some(end), Some(duration)) => format_duration(end.min(duration)),
+            (Some(end), None) => format_duration(end),
+            (None, Some(duration)) => format_duration(duration),
+            (None, None) => "end".into(),
+        };
+        match job.video_duration {
+            Some(duration) => format!("{} - {} (of {})", start, end, format_duration(duration)),
+            None => format!("{} - {}", start, end),
+        }
+    }
+
+    fn attempts_description(&self, job: &job::Job) -> Option<String> {
+        let search = job.search.as_ref()?;
+        let lines: Vec<String> = search
+            .attempts()
+            .iter()
+            .enumerate()
+            .map(|(index, attempt)| {
+                let result = match attempt.size {
+                    Some(size) if size <= search.budget() => {
+                        format!("{} (fits)", format_size(size))
+                    }
+                    Some(size) => format!("{} (over budget)", format_size(size)),
+                    None => "converting...".into(),
+                };
+                format!("Attempt {}: {} - {}", index + 1, attempt, result)
+            })
+            .collect();
+        Some(format!(
+            "Target size: {}\n{}",
+            target_size::format_budget(search.budget()),
+            lines.join("\n")
+        ))
+    }
+
+    fn pending_jobs_count(&self) -> usize {
+        self.jobs
+            .iter()
+            .filter(|job| job.status == job::Status::Pending)
+            .count()
+    }
+
+    fn palette(&self) -> converter::Palette {
+        let default = converter::Palette::default();
+        converter::Palette {
+            max_colors: self.max_colors.unwrap_or(default.max_colors),
+            dither: self.dither,
+            bayer_scale: self.bayer_scale.unwrap_or(default.bayer_scale),
+            stats_mode: self.stats_mode,
+        }
+    }
+
+    /// Saves the preferences without blocking, reporting any failure.
+    fn save_preferences(&self) -> iced::Command<MyMessage> {
+        iced::Command::perform(self.preferences().save_async(), MyMessage::PreferencesSaved)
+    }
+
+    fn preferences(&self) -> preferences::Preferences {
+        let default = preferences::Preferences::default();
+        preferences::Preferences {
+            size_mode: self.size_mode,
+            width: self.gif_width.unwrap_or(default.width),
+            height: self.gif_height.unwrap_or(default.height),
+            size_percent: self.size_percent.unwrap_or(default.size_percent),
+            scaling: self.scaling,
+            fps: self.gif_fps.unwrap_or(default.fps),
+            frame_delay: self.frame_delay.unwrap_or(default.frame_delay),
+            max_colors: self.max_colors.unwrap_or(default.max_colors),
+            dither: self.dither,
+            bayer_scale: self.bayer_scale.unwrap_or(default.bayer_scale),
+            stats_mode: self.stats_mode,
+            format: self.format,
+            target_size: self.target_size,
+            plays: self.plays,
+            overlay_text: self.overlay_text.clone(),
+            overlay_position: self.overlay_position,
+            overlay_font_size: self.overlay_font_size.unwrap_or(default.overlay_font_size),
+            overlay_color: self.overlay_color,
+            overlay_background: self.overlay_background,
+            speed: self.speed,
+            direction: self.direction,
+            open_directory: self.open_directory.clone(),
+            save_directory: self.save_directory.clone(),
+            ffmpeg_path: self.ffmpeg.custom_path(),
+            window_size: self.window_size,
+            window_position: self.window_position,
+        }
+    }
+
+    fn output_description(&self) -> String {
+        let mut description = match self.format {
+            converter::Format::Gif => format!("GIF, {}", self.palette_description()),
+            format => format.to_string(),
+        };
+        if self.speed != converter::Speed::default() {
+            description.push_str(&format!(", {} speed", self.speed));
+        }
+        if self.direction != converter::Direction::default() {
+            description.push_str(&format!(", {}", self.direction.to_string().to_lowercase()));
+        }
+        description
+    }
+
+    fn palette_description(&self) -> String {
+        let palette = self.palette();
+        let dither = if palette.dither == converter::Dither::Bayer {
+            format!("{} (scale {})", palette.dither, palette.bayer_scale)
+        } else {
+            palette.dither.to_string()
+        };
+        format!(
+            "{} colors, {} dithering, {} stats",
+            palette.max_colors,
+            dither,
+            palette.stats_mode.to_string().to_lowercase()
+        )
     }
 
-    fn is_cleared(&self) -> bool {
-        self.video_path.is_none() && self.idle
+    fn crop_description(&self, job: &job::Job) -> String {
+        match (job.crop, job.still.as_ref()) {
+            (Some(crop), _) => format!(
+                "Crop: {}x{} at ({}, {})",
+                crop.width, crop.height, crop.x, crop.y
+            ),
+            (None, Some(still)) => format!("Crop: none ({}x{})", still.width, still.height),
+            (None, None) => "Crop: none".into(),
+        }
     }
 
     fn save_to_file(&self) -> iced::Command<MyMessage> {
         log::debug!(target: LOG_TARGET, "Presenting video file picker (for saving)...");
 
-        let (Some(path), Some(data)) = (&self.video_path, &self.image_data) else {
+        let Some((job, data)) = self
+            .selected_job()
+            .and_then(|job| job.image_data.as_ref().map(|data| (job, data)))
+        else {
             log::error!(target: LOG_TARGET, "This method should not get called while there is no image data.");
             panic!();
         };
 
-        let mut path = path.clone();
-        path.set_extension("gif");
+        let format = job.format;
+        let mut path = job.path.clone();
+        path.set_extension(format.extension());
+
+        // NOTE: The last directory used for saving takes precedence over
+        // the video's directory.
+        let save_directory = self.save_directory.clone();
 
         let data = data.clone();
         let f = async move {
             let file_name = path
                 .file_name()
                 .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or("unnamed.gif".into());
-            let file_directory = path
-                .parent()
+                .unwrap_or(format!("unnamed.{}", format.extension()));
+            let file_directory = save_directory
+                .or(path.parent().map(|d| d.to_path_buf()))
                 .map(|d| d.to_string_lossy().to_string())
                 .unwrap_or("".into());
 
             let result = rfd::AsyncFileDialog::new()
+                .add_filter(format.to_string(), &[format.extension()])
                 .set_file_name(file_name)
                 .set_directory(file_directory)
                 .save_file()
                 .await;
 
             let Some(handle) = result else {
-                return Ok(false);
+                return Ok(None);
             };
 
             tokio::fs::write(handle.path(), data)
                 .await
-                .map(|_| true)
-                .map_err(|e| e.to_string())
+                .map(|_| Some(handle.path().to_path_buf()))
+                .map_err(|e| format!("Failed to save {:?}: {}", handle.path(), e))
         };
 
         log::debug!(target: LOG_TARGET, "Dispatching 'save' command...");
         iced::Command::perform(f, MyMessage::SaveResult)
     }
 
+    /// Exports the selected job's decoded frames (including unapplied
+    /// edits), after asking where to write them.
+    fn export_frames(&self, kind: export::Kind) -> iced::Command<MyMessage> {
+        let Some((job, player)) = self
+            .selected_job()
+            .and_then(|job| job.player.as_ref().map(|player| (job, player)))
+        else {
+            log::error!(target: LOG_TARGET, "This method should not get called while there are no frames.");
+            panic!();
+        };
+
+        log::debug!(target: LOG_TARGET, "Presenting file picker (for exporting {})...", kind);
+        let frames = player.frames().clone();
+        let name = job
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or("frames".into());
+        let directory = self
+            .save_directory
+            .clone()
+            .or(job.path.parent().map(|d| d.to_path_buf()));
+
+        let f = async move {
+            let mut dialog = rfd::AsyncFileDialog::new();
+            if let Some(directory) = directory {
+                dialog = dialog.set_directory(directory);
+            }
+            match kind {
+                export::Kind::Frames => {
+                    let Some(handle) = dialog.pick_folder().await else {
+                        return Ok(None);
+                    };
+                    let directory = handle.path().to_path_buf();
+                    export::frames(frames, directory.clone(), name)
+                        .await
+                        .map(|count| {
+                            Some(format!("{} frame(s) exported to {:?}", count, directory))
+                        })
+                }
+                export::Kind::SpriteSheet => {
+                    let Some(handle) = dialog
+                        .add_filter("PNG", &["png"])
+                        .set_file_name(format!("{}_sheet.png", name))
+                        .save_file()
+                        .await
+                    else {
+                        return Ok(None);
+                    };
+                    export::sprite_sheet(frames, handle.path().to_path_buf())
+                        .await
+                        .map(|manifest| {
+                            Some(format!(
+                                "Sprite sheet exported to {:?}, with manifest {:?}",
+                                handle.path(),
+                                manifest
+                            ))
+                        })
+                }
+            }
+        };
+
+        iced::Command::perform(f, MyMessage::ExportResult)
+    }
+
     fn view_footer(&self) -> iced::Element<'_, MyMessage> {
-        let status_message = if let (Some(_), Some(video_path)) =
-            (self.error_message.as_ref(), self.video_path.as_ref())
-        {
-            format!("Failed to convert file: {:?}", video_path)
-        } else if self.frames.is_some() {
-            "Previewing animated GIF".into()
-        } else if self.image_data.is_some() {
-            "Conversion successful! Loading animated GIF...".into()
-        } else if let Some(path) = self.video_path.as_ref() {
-            format!("Video path: {:?}", path)
-        } else {
-            "".into()
+        let job = self.selected_job();
+
+        let status_message = match job {
+            Some(job) => match &job.status {
+                job::Status::Failed(_) => format!("Failed to convert file: {:?}", job.path),
+                job::Status::Decoding => "Conversion successful! Loading animated GIF...".into(),
+                job::Status::Encoding => "Encoding edited frames...".into(),
+                job::Status::Done if job.player.is_some() => "Previewing animated GIF".into(),
+                job::Status::Done => format!(
+                    "Previewing first frame of animated {} (animation is only previewed for GIF)",
+                    job.format
+                ),
+                _ => format!("Video path: {:?} | {}", job.path, self.output_description()),
+            },
+            None => "".into(),
         };
 
         let text = iced::widget::text(status_message)
@@ -261,7 +1222,9 @@ impl MyApp {
             .spacing(SPACING_SMALL)
             .align_items(iced::Alignment::Center);
 
-        if self.image_data.is_some() && self.frames.is_none() && self.error_message.is_none() {
+        if job
+            .is_some_and(|job| matches!(job.status, job::Status::Decoding | job::Status::Encoding))
+        {
             let loading_indicator =
                 iced_loading_indicator::Widget::new(FOOTER_FONT_SIZE as f32, None, true)
                     .tick_duration_ms(LOADING_INDICATOR_SPEED_MS);
@@ -270,6 +1233,16 @@ impl MyApp {
 
         row = row.push(text);
 
+        let remaining = self.jobs.iter().filter(|job| job.is_in_worker()).count();
+        if remaining > 0 {
+            let text = iced::widget::text(format!("Queue: {} remaining", remaining))
+                .font(self.bold_font())
+                .size(FOOTER_FONT_SIZE);
+            row = row
+                .push(iced::widget::horizontal_space(iced::Length::Fill))
+                .push(text);
+        }
+
         iced::widget::container(row)
             .padding([SPACING_SMALL, SPACING_LARGE])
             .style(styling::CustomContainer::default().move_to_style())
@@ -278,23 +1251,48 @@ impl MyApp {
             .into()
     }
 
-    fn view_toolbar(&self) -> iced::Element<'_, MyMessage> {
+    fn view_toolbar<'a>(&'a self) -> iced::Element<'a, MyMessage> {
         let mut row = iced::widget::Row::new();
+        let job = self.selected_job();
+
+        let text = iced::widget::text("Open".to_uppercase())
+            .font(self.bold_font())
+            .size(TOOLBAR_FONT_SIZE);
+        let mut button = iced::widget::button(text).style(styling::ToolbarButton::default().into());
+        if self.is_ffmpeg_usable() {
+            button = button.on_press(MyMessage::SelectFile);
+        }
+        row = row.push(button);
+
+        let text = iced::widget::text("Open folder".to_uppercase())
+            .font(self.bold_font())
+            .size(TOOLBAR_FONT_SIZE);
+        let mut button = iced::widget::button(text).style(styling::ToolbarButton::default().into());
+        if self.is_ffmpeg_usable() {
+            button = button.on_press(MyMessage::SelectFolder);
+        }
+        row = row.push(button);
 
-        if self.is_cleared() {
-            // NOTE: Could be "!is_working()", but three buttons looks too
-            // busy, so user will have to first clear and then "open" again.
-            let text = iced::widget::text("Open".to_uppercase())
+        let pending_jobs_count = self.pending_jobs_count();
+        if pending_jobs_count > 0 {
+            let text = if pending_jobs_count > 1 {
+                "Convert all"
+            } else {
+                "Convert"
+            };
+            let text = iced::widget::text(text.to_uppercase())
                 .font(self.bold_font())
                 .size(TOOLBAR_FONT_SIZE);
-            let button = iced::widget::button(text)
-                .on_press(MyMessage::SelectFile)
-                .style(styling::ToolbarButton::default().into());
+            let mut button =
+                iced::widget::button(text).style(styling::ToolbarButton::default().into());
+            if self.trim().is_ok() && self.is_ffmpeg_usable() {
+                button = button.on_press(MyMessage::Convert);
+            }
             row = row.push(button);
         }
 
-        if !self.is_cleared() {
-            let text = if self.is_working() { "Cancel" } else { "Clear" }.to_uppercase();
+        if let Some(job) = job {
+            let text = if job.is_working() { "Cancel" } else { "Clear" }.to_uppercase();
             let text = iced::widget::text(text)
                 .font(self.bold_font())
                 .size(TOOLBAR_FONT_SIZE);
@@ -305,7 +1303,7 @@ impl MyApp {
             row = row.push(button);
         }
 
-        if self.image_data.is_some() {
+        if job.is_some_and(|job| job.status == job::Status::Done && job.image_data.is_some()) {
             let text = iced::widget::text("Save".to_uppercase())
                 .font(self.bold_font())
                 .size(TOOLBAR_FONT_SIZE);
@@ -313,18 +1311,206 @@ impl MyApp {
                 .on_press(MyMessage::Save)
                 .style(styling::ToolbarButton::default().into());
             row = row.push(button);
-        }
 
-        row = row.push(iced::widget::horizontal_space(iced::Length::Fill));
+            if job.is_some_and(|job| job.player.is_some()) {
+                for (label, kind) in [
+                    ("Export frames", export::Kind::Frames),
+                    ("Export sheet", export::Kind::SpriteSheet),
+                ] {
+                    let text = iced::widget::text(label.to_uppercase())
+                        .font(self.bold_font())
+                        .size(TOOLBAR_FONT_SIZE);
+                    let button = iced::widget::button(text)
+                        .on_press(MyMessage::Export(kind))
+                        .style(styling::ToolbarButton::text().into());
+                    row = row.push(button);
+                }
+            }
+
+            let text = iced::widget::text("Edit again".to_uppercase())
+                .font(self.bold_font())
+                .size(TOOLBAR_FONT_SIZE);
+            let button = iced::widget::button(text)
+                .on_press(MyMessage::EditAgain)
+                .style(styling::ToolbarButton::text().into());
+            row = row.push(button);
+        }
+
+        let text = iced::widget::text("Settings".to_uppercase())
+            .font(self.bold_font())
+            .size(TOOLBAR_FONT_SIZE);
+        let button = iced::widget::button(text)
+            .on_press(MyMessage::ToggleSettings)
+            .style(styling::ToolbarButton::text().into());
+        row = row.push(button);
+
+        let text = iced::widget::text("Diagnostics".to_uppercase())
+            .font(self.bold_font())
+            .size(TOOLBAR_FONT_SIZE);
+        let button = iced::widget::button(text)
+            .on_press(MyMessage::ToggleDiagnostics)
+            .style(styling::ToolbarButton::text().into());
+        row = row.push(button);
+
+        // NOTE: The conversion inputs below are never disabled, because their
+        // values are captured when jobs get sent to the worker.
+        let input_size = {
+            let mode = iced::widget::pick_list(
+                &converter::SizeMode::ALL[..],
+                Some(self.size_mode),
+                MyMessage::SizeMode,
+            )
+            .font(self.font())
+            .text_size(TOOLBAR_FONT_SIZE)
+            .padding([3.0, 4.0]);
+
+            let input_width = || {
+                numeric_input::NumericInput::new(self.gif_width, MyMessage::Width)
+                    .placeholder(format!("{}", DEFAULT_GIF_WIDTH))
+                    .size(TOOLBAR_FONT_SIZE)
+                    .font(self.font())
+            };
+            let input_height = || {
+                numeric_input::NumericInput::new(self.gif_height, MyMessage::Height)
+                    .placeholder(format!("{}", DEFAULT_GIF_HEIGHT))
+                    .size(TOOLBAR_FONT_SIZE)
+                    .font(self.font())
+            };
+            let unit = |unit: &'static str| {
+                iced::widget::text(unit)
+                    .font(self.bold_font())
+                    .size(TOOLBAR_FONT_SIZE)
+            };
+
+            let mut row = iced::widget::row!(mode)
+                .width(iced::Length::Shrink)
+                .spacing(SPACING_SMALL)
+                .align_items(iced::Alignment::Center);
+            row = match self.size_mode {
+                converter::SizeMode::Width => row.push(input_width()).push(unit("px")),
+                converter::SizeMode::Height => row.push(input_height()).push(unit("px")),
+                converter::SizeMode::Fit | converter::SizeMode::Pad | converter::SizeMode::Fill => {
+                    row.push(input_width())
+                        .push(unit("x"))
+                        .push(input_height())
+                        .push(unit("px"))
+                }
+                converter::SizeMode::Percent => row
+                    .push(
+                        numeric_input::NumericInput::new(self.size_percent, MyMessage::SizePercent)
+                            .placeholder(format!("{}", DEFAULT_SIZE_PERCENT))
+                            .size(TOOLBAR_FONT_SIZE)
+                            .font(self.font()),
+                    )
+                    .push(unit("%")),
+            };
+            row
+        };
 
-        let input_width = {
-            let input = numeric_input::NumericInput::new(self.gif_width, MyMessage::Width)
-                .placeholder(format!("{}", DEFAULT_GIF_WIDTH))
+        let input_transform = {
+            let button = |label: String, is_active: bool, message: MyMessage| {
+                let text = iced::widget::text(label.to_uppercase())
+                    .font(self.bold_font())
+                    .size(TOOLBAR_FONT_SIZE);
+                let style = if is_active {
+                    styling::ToolbarButton::default()
+                } else {
+                    styling::ToolbarButton::text()
+                };
+                iced::widget::button(text)
+                    .on_press(message)
+                    .style(style.into())
+            };
+            let rotation = self.transform.rotation;
+            let rotate = if rotation == converter::Rotation::None {
+                "Rotate".to_string()
+            } else {
+                format!("Rotate {}", rotation)
+            };
+            iced::widget::row!(
+                button(
+                    rotate,
+                    rotation != converter::Rotation::None,
+                    MyMessage::Rotate
+                ),
+                button(
+                    "Flip H".into(),
+                    self.transform.flip_horizontal,
+                    MyMessage::FlipHorizontal
+                ),
+                button(
+                    "Flip V".into(),
+                    self.transform.flip_vertical,
+                    MyMessage::FlipVertical
+                )
+            )
+            .spacing(SPACING_SMALL)
+            .align_items(iced::Alignment::Center)
+        };
+
+        let input_format = {
+            let input = iced::widget::pick_list(
+                &converter::Format::ALL[..],
+                Some(self.format),
+                MyMessage::Format,
+            )
+            .font(self.font())
+            .text_size(TOOLBAR_FONT_SIZE)
+            .padding([3.0, 4.0]);
+
+            let label = iced::widget::text("Format: ")
+                .font(self.bold_font())
+                .size(TOOLBAR_FONT_SIZE);
+
+            iced::widget::row!(label, input)
+                .width(iced::Length::Shrink)
+                .spacing(0)
+                .align_items(iced::Alignment::Center)
+        };
+
+        // NOTE: Image sequences are converted using a frame delay rather
+        // than a frame rate, so the input matching the selected job is shown.
+        let input_fps = if job.is_some_and(|job| job.images.is_some()) {
+            let input = numeric_input::NumericInput::new(self.frame_delay, MyMessage::FrameDelay)
+                .placeholder(format!("{}", DEFAULT_FRAME_DELAY_MS))
                 .size(TOOLBAR_FONT_SIZE)
-                .font(self.font())
-                .disabled(self.is_working());
+                .font(self.font());
+
+            let label = iced::widget::text("Delay (ms): ")
+                .font(self.bold_font())
+                .size(TOOLBAR_FONT_SIZE);
+
+            iced::widget::row!(label, input)
+                .width(iced::Length::Shrink)
+                .spacing(0)
+                .align_items(iced::Alignment::Center)
+        } else {
+            let input = numeric_input::NumericInput::new(self.gif_fps, MyMessage::Fps)
+                .placeholder(format!("{}", DEFAULT_GIF_FPS))
+                .size(TOOLBAR_FONT_SIZE)
+                .font(self.font());
+
+            let label = iced::widget::text("FPS: ")
+                .font(self.bold_font())
+                .size(TOOLBAR_FONT_SIZE);
+
+            iced::widget::row!(label, input)
+                .width(iced::Length::Shrink)
+                .spacing(0)
+                .align_items(iced::Alignment::Center)
+        };
+
+        let input_speed = {
+            let input = iced::widget::pick_list(
+                &converter::Speed::ALL[..],
+                Some(self.speed),
+                MyMessage::Speed,
+            )
+            .font(self.font())
+            .text_size(TOOLBAR_FONT_SIZE)
+            .padding([3.0, 4.0]);
 
-            let label = iced::widget::text("Width (px): ")
+            let label = iced::widget::text("Speed: ")
                 .font(self.bold_font())
                 .size(TOOLBAR_FONT_SIZE);
 
@@ -333,75 +1519,917 @@ impl MyApp {
                 .spacing(0)
                 .align_items(iced::Alignment::Center)
         };
-        row = row.push(input_width);
 
-        row = row
+        let input_direction = {
+            let input = iced::widget::pick_list(
+                &converter::Direction::ALL[..],
+                Some(self.direction),
+                MyMessage::Direction,
+            )
+            .font(self.font())
+            .text_size(TOOLBAR_FONT_SIZE)
+            .padding([3.0, 4.0]);
+
+            let label = iced::widget::text("Direction: ")
+                .font(self.bold_font())
+                .size(TOOLBAR_FONT_SIZE);
+
+            iced::widget::row!(label, input)
+                .width(iced::Length::Shrink)
+                .spacing(0)
+                .align_items(iced::Alignment::Center)
+        };
+
+        let input_trim = {
+            let trim_input = |placeholder: &str, value: &str, on_input: fn(String) -> MyMessage| {
+                iced::widget::text_input(placeholder, value)
+                    .on_input(on_input)
+                    .width(iced::Length::Fixed(50.0))
+                    .padding([3.0, 4.0])
+                    .size(TOOLBAR_FONT_SIZE)
+                    .font(self.font())
+            };
+            let input_start = trim_input("0", &self.trim_start, MyMessage::TrimStart);
+
+            // NOTE: Once the selected video's duration is known, it is used
+            // as the placeholder, so that users can pick the range from it.
+            let end_placeholder = job
+                .and_then(|job| job.video_duration)
+                .map(format_seconds)
+                .unwrap_or("end".into());
+            let input_end = trim_input(&end_placeholder, &self.trim_end, MyMessage::TrimEnd);
+
+            let label = iced::widget::text("Trim (s): ")
+                .font(self.bold_font())
+                .size(TOOLBAR_FONT_SIZE);
+            let separator = iced::widget::text(" - ")
+                .font(self.font())
+                .size(TOOLBAR_FONT_SIZE);
+
+            iced::widget::row!(label, input_start, separator, input_end)
+                .width(iced::Length::Shrink)
+                .spacing(0)
+                .align_items(iced::Alignment::Center)
+        };
+
+        // NOTE: The inputs are split into labelled rows, which scroll
+        // horizontally when the window is too narrow for them.
+        let section = |label: Option<&'static str>, row: iced::widget::Row<'a, MyMessage>| {
+            let mut section = iced::widget::Row::new()
+                .spacing(SPACING_NORMAL)
+                .align_items(iced::Alignment::Center);
+            if let Some(label) = label {
+                section = section.push(
+                    iced::widget::text(label.to_uppercase())
+                        .font(self.bold_font())
+                        .size(FOOTER_FONT_SIZE)
+                        .width(iced::Length::Fixed(TOOLBAR_LABEL_WIDTH)),
+                );
+            }
+            iced::widget::scrollable(section.push(row.spacing(SPACING_NORMAL)))
+                .direction(iced::widget::scrollable::Direction::Horizontal(
+                    iced::widget::scrollable::Properties::default(),
+                ))
+                .width(iced::Length::Fill)
+        };
+
+        let column = iced::widget::column!(
+            section(None, row.align_items(iced::Alignment::Center)),
+            section(
+                Some("Output"),
+                iced::widget::row!(input_format, input_size, input_transform)
+                    .align_items(iced::Alignment::Center)
+            ),
+            section(
+                Some("Timing"),
+                iced::widget::row!(input_fps, input_speed, input_direction, input_trim)
+                    .align_items(iced::Alignment::Center)
+            )
+        )
+        .spacing(SPACING_NORMAL)
+        .width(iced::Length::Fill);
+
+        iced::widget::container(column)
             .width(iced::Length::Fill)
+            .height(iced::Length::Shrink)
+            .style(styling::CustomContainer::toolbar().move_to_style())
+            .padding([SPACING_NORMAL + SPACING_SMALL, SPACING_LARGE])
+            .into()
+    }
+
+    fn view_settings(&self) -> iced::Element<'_, MyMessage> {
+        let label = |label: &'static str| {
+            iced::widget::text(label)
+                .font(self.bold_font())
+                .size(TOOLBAR_FONT_SIZE)
+        };
+
+        let max_colors = iced::widget::row!(
+            label("Max colors: "),
+            numeric_input::NumericInput::new(self.max_colors, MyMessage::MaxColors)
+                .placeholder(format!("{}", converter::Palette::MAX_COLORS))
+                .size(TOOLBAR_FONT_SIZE)
+                .font(self.font())
+        )
+        .align_items(iced::Alignment::Center);
+
+        let dither = iced::widget::row!(
+            label("Dither: "),
+            iced::widget::pick_list(
+                &converter::Dither::ALL[..],
+                Some(self.dither),
+                MyMessage::Dither,
+            )
+            .font(self.font())
+            .text_size(TOOLBAR_FONT_SIZE)
+            .padding([3.0, 4.0])
+        )
+        .align_items(iced::Alignment::Center);
+
+        let bayer_scale = iced::widget::row!(
+            label("Bayer scale: "),
+            numeric_input::NumericInput::new(self.bayer_scale, MyMessage::BayerScale)
+                .placeholder(format!("{}", converter::Palette::default().bayer_scale))
+                .size(TOOLBAR_FONT_SIZE)
+                .font(self.font())
+                .disabled(self.dither != converter::Dither::Bayer)
+        )
+        .align_items(iced::Alignment::Center);
+
+        let stats_mode = iced::widget::row!(
+            label("Stats mode: "),
+            iced::widget::pick_list(
+                &converter::StatsMode::ALL[..],
+                Some(self.stats_mode),
+                MyMessage::StatsMode,
+            )
+            .font(self.font())
+            .text_size(TOOLBAR_FONT_SIZE)
+            .padding([3.0, 4.0])
+        )
+        .align_items(iced::Alignment::Center);
+
+        // NOTE: When set, the conversion is run again with lower settings
+        // until the output fits within the target size.
+        let target_size = iced::widget::row!(
+            label("Target size (KiB): "),
+            numeric_input::NumericInput::new(self.target_size, MyMessage::TargetSize)
+                .placeholder("none (KiB)")
+                .size(TOOLBAR_FONT_SIZE)
+                .font(self.font())
+        )
+        .align_items(iced::Alignment::Center);
+
+        // NOTE: Leaving it empty (or setting 0) makes the animation loop
+        // forever.
+        let plays = iced::widget::row!(
+            label("Plays: "),
+            numeric_input::NumericInput::new(self.plays, MyMessage::Plays)
+                .placeholder("forever")
+                .size(TOOLBAR_FONT_SIZE)
+                .font(self.font())
+        )
+        .align_items(iced::Alignment::Center);
+
+        let scaling = iced::widget::row!(
+            label("Scaling: "),
+            iced::widget::pick_list(
+                &converter::ScaleAlgorithm::ALL[..],
+                Some(self.scaling),
+                MyMessage::Scaling,
+            )
+            .font(self.font())
+            .text_size(TOOLBAR_FONT_SIZE)
+            .padding([3.0, 4.0])
+        )
+        .align_items(iced::Alignment::Center);
+
+        let row = iced::widget::row!(
+            scaling,
+            max_colors,
+            dither,
+            bayer_scale,
+            stats_mode,
+            plays,
+            target_size
+        )
+        .spacing(SPACING_LARGE)
+        .align_items(iced::Alignment::Center)
+        .width(iced::Length::Fill);
+
+        // NOTE: The overlay is previewed on the selected job's still frame.
+        let overlay_text = iced::widget::row!(
+            label("Caption: "),
+            iced::widget::text_input("none", &self.overlay_text)
+                .on_input(MyMessage::OverlayText)
+                .width(iced::Length::Fixed(200.0))
+                .padding([3.0, 4.0])
+                .font(self.font())
+                .size(TOOLBAR_FONT_SIZE)
+        )
+        .align_items(iced::Alignment::Center);
+
+        let overlay_position = iced::widget::row!(
+            label("Position: "),
+            iced::widget::pick_list(
+                &overlay::Position::ALL[..],
+                Some(self.overlay_position),
+                MyMessage::OverlayPosition,
+            )
+            .font(self.font())
+            .text_size(TOOLBAR_FONT_SIZE)
+            .padding([3.0, 4.0])
+        )
+        .align_items(iced::Alignment::Center);
+
+        let overlay_font_size = iced::widget::row!(
+            label("Font size: "),
+            numeric_input::NumericInput::new(self.overlay_font_size, MyMessage::OverlayFontSize)
+                .placeholder(format!("{}", overlay::Overlay::DEFAULT_FONT_SIZE))
+                .size(TOOLBAR_FONT_SIZE)
+                .font(self.font())
+        )
+        .align_items(iced::Alignment::Center);
+
+        let overlay_color = iced::widget::row!(
+            label("Color: "),
+            iced::widget::pick_list(
+                &overlay::Color::ALL[..],
+                Some(self.overlay_color),
+                MyMessage::OverlayColor,
+            )
+            .font(self.font())
+            .text_size(TOOLBAR_FONT_SIZE)
+            .padding([3.0, 4.0])
+        )
+        .align_items(iced::Alignment::Center);
+
+        let overlay_background = iced::widget::checkbox(
+            "Background",
+            self.overlay_background,
+            MyMessage::OverlayBackground,
+        )
+        .font(self.bold_font())
+        .text_size(TOOLBAR_FONT_SIZE);
+
+        let overlay_row = iced::widget::row!(
+            overlay_text,
+            overlay_position,
+            overlay_font_size,
+            overlay_color,
+            overlay_background
+        )
+        .spacing(SPACING_LARGE)
+        .align_items(iced::Alignment::Center)
+        .width(iced::Length::Fill);
+
+        let ffmpeg_button = |label: &'static str, message: MyMessage| {
+            let text = iced::widget::text(label.to_uppercase())
+                .font(self.bold_font())
+                .size(TOOLBAR_FONT_SIZE);
+            iced::widget::button(text)
+                .on_press(message)
+                .padding(0)
+                .style(styling::ToolbarButton::text().into())
+        };
+        let mut ffmpeg_row = iced::widget::row!(
+            label("FFmpeg: "),
+            iced::widget::text(format!(
+                "{} (from the {})",
+                self.ffmpeg.path, self.ffmpeg.source
+            ))
+            .font(self.font())
+            .size(TOOLBAR_FONT_SIZE),
+            ffmpeg_button("Browse", MyMessage::SelectFfmpeg)
+        )
+        .spacing(SPACING_NORMAL)
+        .align_items(iced::Alignment::Center)
+        .width(iced::Length::Fill);
+        if self.ffmpeg.source == diagnostics::Source::Settings {
+            ffmpeg_row = ffmpeg_row.push(ffmpeg_button("Reset", MyMessage::ResetFfmpeg));
+        }
+
+        let column = iced::widget::column!(row, overlay_row, ffmpeg_row)
+            .spacing(SPACING_NORMAL)
+            .width(iced::Length::Fill);
+
+        iced::widget::container(column)
+            .width(iced::Length::Fill)
+            .height(iced::Length::Shrink)
+            .style(styling::CustomContainer::sidebar().move_to_style())
+            .padding([SPACING_NORMAL, SPACING_LARGE])
+            .into()
+    }
+
+    fn view_diagnostics(&self) -> iced::Element<'_, MyMessage> {
+        let line = |label: &'static str, value: String| {
+            iced::widget::row!(
+                iced::widget::text(label)
+                    .font(self.bold_font())
+                    .size(TOOLBAR_FONT_SIZE),
+                iced::widget::text(value)
+                    .font(self.font())
+                    .size(TOOLBAR_FONT_SIZE)
+            )
             .align_items(iced::Alignment::Center)
+        };
+
+        let mut column = iced::widget::column!(line(
+            "FFmpeg binary: ",
+            format!("{} (from the {})", self.ffmpeg.path, self.ffmpeg.source)
+        ))
+        .spacing(SPACING_SMALL)
+        .width(iced::Length::Fill);
+
+        match self.diagnostics.as_ref() {
+            None => column = column.push(line("Version: ", "checking...".into())),
+            Some(report) => {
+                let version = match &report.version {
+                    Ok(version) => version.clone(),
+                    Err(_) => "unknown".into(),
+                };
+                column = column.push(line("Version: ", version));
+                if report.version.is_ok() {
+                    let filters = if report.missing_filters.is_empty() {
+                        "all required filters are available".into()
+                    } else {
+                        format!("missing {}", report.missing_filters.join(", "))
+                    };
+                    column = column.push(line("Filters: ", filters));
+                }
+                let status = report.problem().unwrap_or("Ready to convert.".into());
+                column = column.push(line("Status: ", status));
+            }
+        }
+
+        let text = iced::widget::text("Check again".to_uppercase())
+            .font(self.bold_font())
+            .size(TOOLBAR_FONT_SIZE);
+        let mut button = iced::widget::button(text)
+            .padding(0)
+            .style(styling::ToolbarButton::text().into());
+        if self.diagnostics.is_some() {
+            button = button.on_press(MyMessage::ProbeFfmpeg);
+        }
+        let text = iced::widget::text("Choose binary".to_uppercase())
+            .font(self.bold_font())
+            .size(TOOLBAR_FONT_SIZE);
+        let choose = iced::widget::button(text)
+            .on_press(MyMessage::SelectFfmpeg)
+            .padding(0)
+            .style(styling::ToolbarButton::text().into());
+        column = column.push(iced::widget::row!(button, choose).spacing(SPACING_LARGE));
+
+        iced::widget::container(column)
+            .width(iced::Length::Fill)
+            .height(iced::Length::Shrink)
+            .style(styling::CustomContainer::sidebar().move_to_style())
+            .padding([SPACING_NORMAL, SPACING_LARGE])
+            .into()
+    }
+
+    fn view_queue(&self) -> iced::Element<'_, MyMessage> {
+        let mut column = iced::widget::Column::new()
             .spacing(SPACING_NORMAL)
-            .height(iced::Length::Shrink);
+            .width(iced::Length::Fill);
+
+        for job in self.jobs.iter() {
+            let name = iced::widget::text(job.file_name())
+                .font(self.bold_font())
+                .size(FOOTER_FONT_SIZE);
+            let status = iced::widget::text(job.status_description())
+                .font(self.font())
+                .size(FOOTER_FONT_SIZE);
+
+            let style = if self.selected_job == Some(job.id) {
+                styling::ToolbarButton::default()
+            } else {
+                styling::ToolbarButton::text()
+            };
+            let select = iced::widget::button(iced::widget::column!(name, status))
+                .on_press(MyMessage::SelectJob(job.id))
+                .padding(0)
+                .width(iced::Length::Fill)
+                .style(style.into());
+
+            let text = iced::widget::text("×")
+                .font(self.bold_font())
+                .size(TOOLBAR_FONT_SIZE);
+            let remove = iced::widget::button(text)
+                .on_press(MyMessage::RemoveJob(job.id))
+                .padding(0)
+                .style(styling::ToolbarButton::destructive().into());
+
+            column = column.push(
+                iced::widget::row!(select, remove)
+                    .spacing(SPACING_SMALL)
+                    .align_items(iced::Alignment::Center),
+            );
+        }
+
+        iced::widget::container(iced::widget::scrollable(column))
+            .width(iced::Length::Fixed(SIDEBAR_WIDTH))
+            .height(iced::Length::Fill)
+            .style(styling::CustomContainer::sidebar().move_to_style())
+            .padding([SPACING_NORMAL, SPACING_NORMAL])
+            .into()
+    }
+
+    fn view_job(&self, job: &job::Job) -> iced::Element<'_, MyMessage> {
+        match &job.status {
+            job::Status::Failed(error_message) => {
+                let title = iced::widget::text("This video could not be converted.")
+                    .font(self.bold_font())
+                    .size(CONTENT_FONT_SIZE);
+                // NOTE: The error also stays here once its notification has
+                // been dismissed.
+                let error = iced::widget::text(error_message)
+                    .font(self.font())
+                    .size(FOOTER_FONT_SIZE);
+                let column = iced::widget::column!(title, error)
+                    .spacing(SPACING_SMALL)
+                    .align_items(iced::Alignment::Center);
+                iced::widget::container(column)
+                    .padding(SPACING_NORMAL)
+                    .style(styling::CustomContainer::error().move_to_style())
+                    .into()
+            }
+            job::Status::Decoding | job::Status::Encoding | job::Status::Done => {
+                let preview: iced::Element<'_, MyMessage> = if let Some(player) =
+                    job.player.as_ref()
+                {
+                    self.view_player(player)
+                } else if let Some(data) = &job.image_data {
+                    iced::widget::Image::new(iced::widget::image::Handle::from_memory(data.clone()))
+                        .content_fit(iced::ContentFit::ScaleDown)
+                        .width(iced::Length::Fill)
+                        .height(iced::Length::Fill)
+                        .into()
+                } else {
+                    iced::widget::text("").into()
+                };
+
+                let mut column = iced::widget::column!(preview)
+                    .spacing(SPACING_NORMAL)
+                    .width(iced::Length::Fill)
+                    .height(iced::Length::Fill);
+                if let Some(player) = job.player.as_ref().filter(|_| self.show_frame_editor) {
+                    column = column.push(self.view_frame_editor(job, player));
+                }
+                column.push(self.view_info(job)).into()
+            }
+            job::Status::Converting | job::Status::Queued => {
+                let message = if job.status == job::Status::Queued {
+                    let position = self
+                        .jobs
+                        .iter()
+                        .filter(|other| other.is_in_worker())
+                        .position(|other| other.id == job.id)
+                        .unwrap_or_default();
+                    format!(
+                        "Waiting for {} job(s) ahead in the queue...\nRange: {}",
+                        position,
+                        self.trim_description(job)
+                    )
+                } else if let (Some(_), Some(progress)) =
+                    (job.video_duration.as_ref(), job.progress.as_ref())
+                {
+                    format!(
+                        "Processing frames - {:.0}%\nRange: {}",
+                        progress * 100.0,
+                        self.trim_description(job)
+                    )
+                } else if let Some(video_duration) = job.video_duration.as_ref() {
+                    format!(
+                        "Video duration parsed ({:?}). Waiting for frame processing to start...\nRange: {}",
+                        video_duration,
+                        self.trim_description(job)
+                    )
+                } else {
+                    "Creating FFmpeg task...".into()
+                };
+                let message = match self.attempts_description(job) {
+                    Some(attempts) => format!("{}\n\n{}", message, attempts),
+                    None => message,
+                };
+
+                let text = iced::widget::text(message)
+                    .font(self.font())
+                    .size(CONTENT_FONT_SIZE);
+
+                let loading_indicator =
+                    iced_loading_indicator::Widget::new(LOADING_INDICATOR_SIZE, None, true)
+                        .tick_duration_ms(LOADING_INDICATOR_SPEED_MS);
+
+                iced::widget::container(
+                    iced::widget::column!(loading_indicator, text)
+                        .align_items(iced::Alignment::Center)
+                        .spacing(SPACING_LARGE)
+                        .width(iced::Length::Fill),
+                )
+                .padding(0)
+                .center_x()
+                .width(iced::Length::Fill)
+                .into()
+            }
+            job::Status::Pending => {
+                let Some(still) = job.still.as_ref() else {
+                    return iced::widget::text("").into();
+                };
+
+                // NOTE: The overlay's preview is scaled from the output's
+                // width, which depends on the converted region's size.
+                let (mut region_width, mut region_height) = job
+                    .crop
+                    .map(|crop| (crop.width, crop.height))
+                    .unwrap_or((still.width, still.height));
+                if self.transform.swaps_dimensions() {
+                    std::mem::swap(&mut region_width, &mut region_height);
+                }
+                let (output_width, _) = self.size().output_size(region_width, region_height);
+
+                // NOTE: Animated inputs get played within the selector.
+                let selector = match job.input_player.as_ref() {
+                    Some(player) => crop_selector::CropSelector::new(
+                        player.current().handle.clone(),
+                        iced::Size::new(player.frames().width(), player.frames().height()),
+                        job.crop,
+                        MyMessage::Crop,
+                    ),
+                    None => crop_selector::CropSelector::new(
+                        still.handle.clone(),
+                        iced::Size::new(still.width, still.height),
+                        job.crop,
+                        MyMessage::Crop,
+                    ),
+                }
+                .overlay(
+                    self.overlay(),
+                    output_width.min(u16::MAX as u32) as u16,
+                    self.bold_font(),
+                );
+
+                let hint = match self.trim() {
+                    Ok(_) => {
+                        "Drag on the frame to select a crop region, then click on CONVERT.".into()
+                    }
+                    Err(e) => e,
+                };
+                let hint = iced::widget::text(hint)
+                    .font(self.font())
+                    .size(FOOTER_FONT_SIZE);
+                let description = iced::widget::text(self.crop_description(job))
+                    .font(self.font())
+                    .size(FOOTER_FONT_SIZE);
+
+                let mut details = iced::widget::Row::new()
+                    .spacing(SPACING_NORMAL)
+                    .align_items(iced::Alignment::Center)
+                    .push(hint)
+                    .push(iced::widget::horizontal_space(iced::Length::Fill))
+                    .push(description);
+                if job.crop.is_some() {
+                    let text = iced::widget::text("Reset".to_uppercase())
+                        .font(self.bold_font())
+                        .size(FOOTER_FONT_SIZE);
+                    let button = iced::widget::button(text)
+                        .on_press(MyMessage::Crop(None))
+                        .padding(0)
+                        .style(styling::ToolbarButton::text().into());
+                    details = details.push(button);
+                }
+
+                let mut column = iced::widget::column!(selector)
+                    .spacing(SPACING_NORMAL)
+                    .width(iced::Length::Fill)
+                    .height(iced::Length::Fill);
+                if let Some(player) = job.input_player.as_ref() {
+                    column = column.push(self.view_playback_controls(player));
+                }
+                column = column.push(details);
+                if let Some(oriented) = still.oriented.as_ref() {
+                    let label =
+                        iced::widget::text(format!("Output orientation ({}):", self.transform))
+                            .font(self.font())
+                            .size(FOOTER_FONT_SIZE);
+                    let image = iced::widget::image(oriented.clone())
+                        .height(iced::Length::Fixed(ORIENTATION_PREVIEW_HEIGHT));
+                    column = column.push(
+                        iced::widget::row!(label, image)
+                            .spacing(SPACING_NORMAL)
+                            .align_items(iced::Alignment::Center),
+                    );
+                }
+                if let Some(metadata) = job.metadata.as_ref() {
+                    column = column.push(self.view_metadata(metadata));
+                }
+                column.into()
+            }
+            job::Status::Loading => {
+                let text = iced::widget::text("Loading video preview...")
+                    .font(self.font())
+                    .size(CONTENT_FONT_SIZE);
+
+                let loading_indicator =
+                    iced_loading_indicator::Widget::new(LOADING_INDICATOR_SIZE, None, true)
+                        .tick_duration_ms(LOADING_INDICATOR_SPEED_MS);
+
+                iced::widget::column!(loading_indicator, text)
+                    .align_items(iced::Alignment::Center)
+                    .spacing(SPACING_LARGE)
+                    .into()
+            }
+        }
+    }
+
+    /// Describes the source video, warning about properties that may spoil
+    /// the conversion, and suggests a width and frame rate for it.
+    fn view_metadata(&self, metadata: &metadata::Metadata) -> iced::Element<'_, MyMessage> {
+        let mut description = format!("Source: {}", metadata);
+        if metadata.is_portrait() {
+            description.push_str(" | Portrait video");
+        }
+        if metadata.is_variable_frame_rate() {
+            description.push_str(" | Variable frame rate (timing may look uneven)");
+        }
+        let description = iced::widget::text(description)
+            .font(self.font())
+            .size(FOOTER_FONT_SIZE);
+
+        let mut row = iced::widget::Row::new()
+            .spacing(SPACING_NORMAL)
+            .align_items(iced::Alignment::Center)
+            .push(description)
+            .push(iced::widget::horizontal_space(iced::Length::Fill));
+
+        let suggestion = match (metadata.suggested_width(), metadata.suggested_fps()) {
+            (Some(width), Some(fps)) => Some(format!("{} px, {} FPS", width, fps)),
+            (Some(width), None) => Some(format!("{} px", width)),
+            (None, Some(fps)) => Some(format!("{} FPS", fps)),
+            (None, None) => None,
+        };
+        if let Some(suggestion) = suggestion {
+            let text = iced::widget::text(format!("Use {}", suggestion).to_uppercase())
+                .font(self.bold_font())
+                .size(FOOTER_FONT_SIZE);
+            let button = iced::widget::button(text)
+                .on_press(MyMessage::ApplySuggestedSettings)
+                .padding(0)
+                .style(styling::ToolbarButton::text().into());
+            row = row.push(button);
+        }
+        row.into()
+    }
+
+    /// Describes the produced file, so that users can decide whether to
+    /// convert again with different settings before saving.
+    fn view_info(&self, job: &job::Job) -> iced::Element<'_, MyMessage> {
+        let mut details = vec![];
+        if let Some(data) = job.image_data.as_ref() {
+            details.push(format!("Size: {}", format_size(data.len())));
+        }
+        if let Some(frames) = job.player.as_ref().map(|player| player.frames()) {
+            details.push(format!("{}x{} px", frames.width(), frames.height()));
+            details.push(format!("{} frames", frames.len()));
+            details.push(format!("Duration: {}", format_timestamp(frames.duration())));
+            details.push(format!(
+                "Average delay: {} ms",
+                frames.average_delay().as_millis()
+            ));
+        }
+        if job.image_data.is_some() {
+            details.push(format!("Plays {}", job.looping));
+        }
+
+        let text = iced::widget::text(details.join(" | "))
+            .font(self.bold_font())
+            .size(FOOTER_FONT_SIZE);
+        let mut column = iced::widget::column!(text)
+            .spacing(SPACING_SMALL)
+            .align_items(iced::Alignment::Center);
+
+        if let Some(attempts) = self.attempts_description(job) {
+            column = column.push(
+                iced::widget::text(attempts)
+                    .font(self.font())
+                    .size(FOOTER_FONT_SIZE),
+            );
+        }
+
+        if job.player.is_some() {
+            let label = if self.show_frame_editor {
+                "Close editor"
+            } else {
+                "Edit frames"
+            };
+            let text = iced::widget::text(label.to_uppercase())
+                .font(self.bold_font())
+                .size(FOOTER_FONT_SIZE);
+            column = column.push(
+                iced::widget::button(text)
+                    .on_press(MyMessage::ToggleFrameEditor)
+                    .padding(0)
+                    .style(styling::ToolbarButton::text().into()),
+            );
+        }
+
+        iced::widget::container(column)
+            .width(iced::Length::Fill)
+            .center_x()
+            .into()
+    }
+
+    /// Shows the output's frames as a filmstrip, along with the controls
+    /// editing the selected one. Edits only reach the output once applied.
+    fn view_frame_editor(
+        &self,
+        job: &job::Job,
+        player: &preview::Player,
+    ) -> iced::Element<'_, MyMessage> {
+        let is_editable = job.status == job::Status::Done;
+
+        let mut filmstrip = iced::widget::Row::new()
+            .spacing(SPACING_SMALL)
+            .align_items(iced::Alignment::Center);
+        for (index, frame) in player.frames().iter().enumerate() {
+            let thumbnail = iced::widget::image(frame.handle.clone())
+                .height(iced::Length::Fixed(FILMSTRIP_THUMBNAIL_HEIGHT));
+            let delay = iced::widget::text(format!("{} ms", frame.delay.as_millis()))
+                .font(self.font())
+                .size(FOOTER_FONT_SIZE);
+            let style = if index == player.index() {
+                styling::ToolbarButton::default()
+            } else {
+                styling::ToolbarButton::text()
+            };
+            let button = iced::widget::button(
+                iced::widget::column!(thumbnail, delay)
+                    .spacing(SPACING_SMALL)
+                    .align_items(iced::Alignment::Center),
+            )
+            .on_press(MyMessage::SeekFrame(index as u32))
+            .padding(SPACING_SMALL)
+            .style(style.into());
+            filmstrip = filmstrip.push(button);
+        }
+        let filmstrip = iced::widget::scrollable(filmstrip)
+            .direction(iced::widget::scrollable::Direction::Horizontal(
+                iced::widget::scrollable::Properties::default(),
+            ))
+            .width(iced::Length::Fill);
+
+        let button = |label: &'static str, message: Option<MyMessage>| {
+            let text = iced::widget::text(label.to_uppercase())
+                .font(self.bold_font())
+                .size(FOOTER_FONT_SIZE);
+            let button = iced::widget::button(text)
+                .padding(0)
+                .style(styling::ToolbarButton::text().into());
+            match message.filter(|_| is_editable) {
+                Some(message) => button.on_press(message),
+                None => button,
+            }
+        };
+
+        let delay_label = iced::widget::text("Delay (ms)")
+            .font(self.font())
+            .size(FOOTER_FONT_SIZE);
+        let delay_input = numeric_input::NumericInput::new(
+            Some(player.current().delay.as_millis() as u32),
+            MyMessage::EditFrameDelay,
+        )
+        .font(self.font())
+        .size(FOOTER_FONT_SIZE)
+        .disabled(!is_editable);
+        let delay_input = iced::widget::container(delay_input).width(iced::Length::Fixed(80.0));
+
+        let hint = if job.frames_edited {
+            "Apply the edits before saving, so that they end up in the file."
+        } else {
+            "Select a frame to delete, duplicate or retime it."
+        };
+        let hint = iced::widget::text(hint)
+            .font(self.font())
+            .size(FOOTER_FONT_SIZE);
+
+        let controls = iced::widget::row!(
+            button(
+                "Delete",
+                (player.frames().len() > 1).then_some(MyMessage::DeleteFrame)
+            ),
+            button("Duplicate", Some(MyMessage::DuplicateFrame)),
+            delay_label,
+            delay_input,
+            hint,
+            iced::widget::horizontal_space(iced::Length::Fill),
+            button(
+                "Apply edits",
+                job.frames_edited.then_some(MyMessage::ApplyFrameEdits)
+            )
+        )
+        .spacing(SPACING_NORMAL)
+        .align_items(iced::Alignment::Center);
+
+        iced::widget::column!(filmstrip, controls)
+            .spacing(SPACING_SMALL)
+            .width(iced::Length::Fill)
+            .into()
+    }
+
+    fn view_player(&self, player: &preview::Player) -> iced::Element<'_, MyMessage> {
+        let image = iced::widget::image(player.current().handle.clone())
+            .content_fit(iced::ContentFit::ScaleDown)
+            .width(iced::Length::Fill)
+            .height(iced::Length::Fill);
 
-        iced::widget::container(row)
+        iced::widget::column!(image, self.view_playback_controls(player))
+            .spacing(SPACING_NORMAL)
             .width(iced::Length::Fill)
-            .height(iced::Length::Shrink)
-            .style(styling::CustomContainer::toolbar().move_to_style())
-            .padding([SPACING_NORMAL + SPACING_SMALL, SPACING_LARGE])
+            .height(iced::Length::Fill)
             .into()
     }
 
-    fn view_content(&self) -> iced::Element<'_, MyMessage> {
-        let element: iced::Element<'_, MyMessage> = if let Some(error_message) =
-            self.error_message.as_ref()
-        {
-            let text = iced::widget::text(format!("[ERROR] {}", error_message))
-                .font(self.font())
-                .size(CONTENT_FONT_SIZE);
-            iced::widget::container(text).into()
-        } else if let Some(frames) = self.frames.as_ref() {
-            let image = iced_gif::gif(frames).content_fit(iced::ContentFit::ScaleDown);
-            image.into()
-        } else if let Some(data) = &self.image_data {
-            let image =
-                iced::widget::Image::new(iced::widget::image::Handle::from_memory(data.clone()))
-                    .content_fit(iced::ContentFit::ScaleDown);
-            image.into()
-        } else if self.is_working() {
-            let message = if let (Some(_), Some(progress)) =
-                (self.video_duration.as_ref(), self.progress.as_ref())
-            {
-                format!("Processing frames - {:.0}%", progress * 100.0)
-            } else if let Some(video_duration) = self.video_duration.as_ref() {
-                format!(
-                    "Video duration parsed ({:?}). Waiting for frame processing to start...",
-                    video_duration
-                )
-            } else {
-                "Creating FFmpeg task...".into()
-            };
+    fn view_playback_controls(&self, player: &preview::Player) -> iced::Element<'_, MyMessage> {
+        let button = |label: &'static str, message: MyMessage| {
+            let text = iced::widget::text(label.to_uppercase())
+                .font(self.bold_font())
+                .size(FOOTER_FONT_SIZE);
+            iced::widget::button(text)
+                .on_press(message)
+                .padding(0)
+                .style(styling::ToolbarButton::text().into())
+        };
 
-            let text = iced::widget::text(message)
-                .font(self.font())
-                .size(CONTENT_FONT_SIZE);
+        let frames = player.frames();
+        let last_index = frames.len().saturating_sub(1) as u32;
+        let slider =
+            iced::widget::slider(0..=last_index, player.index() as u32, MyMessage::SeekFrame)
+                .width(iced::Length::Fill);
+
+        let readout = iced::widget::text(format!(
+            "Frame {}/{} | {} / {}",
+            player.index() + 1,
+            frames.len(),
+            format_timestamp(frames.timestamp(player.index())),
+            format_timestamp(frames.duration())
+        ))
+        .font(self.font())
+        .size(FOOTER_FONT_SIZE);
+
+        iced::widget::row!(
+            button(
+                if player.is_playing() { "Pause" } else { "Play" },
+                MyMessage::PlayPause
+            ),
+            button("<", MyMessage::StepFrame(-1)),
+            button(">", MyMessage::StepFrame(1)),
+            slider,
+            readout
+        )
+        .spacing(SPACING_NORMAL)
+        .align_items(iced::Alignment::Center)
+        .into()
+    }
 
-            let loading_indicator =
-                iced_loading_indicator::Widget::new(LOADING_INDICATOR_SIZE, None, true)
-                    .tick_duration_ms(LOADING_INDICATOR_SPEED_MS);
+    /// Why FFmpeg cannot be used (according to its diagnostics), if it
+    /// cannot, along with how to fix it.
+    fn ffmpeg_problem(&self) -> Option<String> {
+        let problem = self
+            .diagnostics
+            .as_ref()
+            .and_then(|report| report.problem())?;
+        Some(format!(
+            "{} Install FFmpeg, or choose its binary from the diagnostics panel (or the settings panel).",
+            problem
+        ))
+    }
 
-            iced::widget::container(
-                iced::widget::column!(loading_indicator, text)
-                    .align_items(iced::Alignment::Center)
-                    .spacing(SPACING_LARGE)
-                    .width(iced::Length::Fill),
+    fn view_content(&self) -> iced::Element<'_, MyMessage> {
+        let problem = self.ffmpeg_problem();
+        let element: iced::Element<'_, MyMessage> = match (self.selected_job(), problem) {
+            // NOTE: Jobs remain viewable (e.g. to save a converted one), but
+            // the problem stays displayed above them.
+            (Some(job), Some(problem)) => {
+                let banner = iced::widget::container(
+                    iced::widget::text(problem)
+                        .font(self.font())
+                        .size(CONTENT_FONT_SIZE),
+                )
+                .width(iced::Length::Fill)
+                .padding(SPACING_NORMAL)
+                .style(styling::CustomContainer::error().move_to_style());
+                iced::widget::column!(banner, self.view_job(job))
+                    .spacing(SPACING_NORMAL)
+                    .into()
+            }
+            (Some(job), None) => self.view_job(job),
+            (None, problem) => iced::widget::text(
+                problem
+                    .unwrap_or("Select videos, GIFs or images, or drag-and-drop them here".into()),
             )
-            .padding(0)
-            .center_x()
-            .width(iced::Length::Fill)
-            .into()
-        } else {
-            iced::widget::text("Select a video file or drag-and-drop one here")
-                .font(self.font())
-                .size(CONTENT_FONT_SIZE)
-                .into()
+            .font(self.font())
+            .size(CONTENT_FONT_SIZE)
+            .into(),
         };
 
         iced::widget::container(element)
@@ -413,6 +2441,37 @@ impl MyApp {
             .into()
     }
 
+    fn view_notifications(&self) -> iced::Element<'_, MyMessage> {
+        let mut column = iced::widget::Column::new().spacing(SPACING_SMALL);
+
+        for notification in self.notifications.iter() {
+            let text = iced::widget::text(&notification.message)
+                .font(self.font())
+                .size(FOOTER_FONT_SIZE)
+                .width(iced::Length::Fill);
+
+            let dismiss = iced::widget::text("Dismiss".to_uppercase())
+                .font(self.bold_font())
+                .size(FOOTER_FONT_SIZE);
+            let button = iced::widget::button(dismiss)
+                .on_press(MyMessage::DismissNotification(notification.id))
+                .padding(0)
+                .style(styling::ToolbarButton::text().into());
+
+            column = column.push(
+                iced::widget::row!(text, button)
+                    .spacing(SPACING_NORMAL)
+                    .align_items(iced::Alignment::Center),
+            );
+        }
+
+        iced::widget::container(column)
+            .width(iced::Length::Fill)
+            .padding([SPACING_SMALL, SPACING_LARGE])
+            .style(styling::CustomContainer::error().move_to_style())
+            .into()
+    }
+
     fn view_full(&self) -> iced::Element<'_, MyMessage> {
         let toolbar = self.view_toolbar();
         let footer = self.view_footer();
@@ -421,7 +2480,39 @@ impl MyApp {
 
         let content = self.view_content();
 
-        let column = iced::widget::column!(toolbar, divider_toolbar, content, footer)
+        let mut column = iced::widget::column!(toolbar, divider_toolbar);
+        if self.show_settings {
+            let divider_settings =
+                iced::widget::horizontal_rule(0).style(styling::CustomRule::dark().move_to_style());
+            column = column.push(self.view_settings()).push(divider_settings);
+        }
+        if self.show_diagnostics {
+            let divider_diagnostics =
+                iced::widget::horizontal_rule(0).style(styling::CustomRule::dark().move_to_style());
+            column = column
+                .push(self.view_diagnostics())
+                .push(divider_diagnostics);
+        }
+
+        if !self.notifications.is_empty() {
+            column = column.push(self.view_notifications());
+        }
+
+        // NOTE: The queue is only displayed when there is more than one job,
+        // to keep the single video use case uncluttered.
+        let content: iced::Element<'_, MyMessage> = if self.jobs.len() > 1 {
+            let divider_queue =
+                iced::widget::vertical_rule(0).style(styling::CustomRule::dark().move_to_style());
+            iced::widget::row!(self.view_queue(), divider_queue, content)
+                .height(iced::Length::Fill)
+                .into()
+        } else {
+            content
+        };
+
+        let column = column
+            .push(content)
+            .push(footer)
             .width(iced::Length::Fill)
             .height(iced::Length::Fill)
             .align_items(iced::Alignment::Center)
@@ -436,19 +2527,34 @@ impl MyApp {
 
 impl iced::Application for MyApp {
     type Executor = iced::executor::Default;
-    type Flags = ();
+    type Flags = Flags;
     type Message = MyMessage;
     type Theme = iced::theme::Theme;
 
     fn subscription(&self) -> iced::Subscription<Self::Message> {
-        iced::Subscription::batch(vec![
+        let mut subscriptions = vec![
             iced::subscription::events().map(MyMessage::Event),
             worker::worker().map(MyMessage::WorkerEvent),
-        ])
+        ];
+
+        // NOTE: Only the selected job's preview gets played.
+        if let Some(player) = self
+            .selected_job()
+            .and_then(|job| job.active_player())
+            .filter(|player| player.is_playing())
+        {
+            // NOTE: Edited frames may have no delay, which `every` does not
+            // accept.
+            subscriptions.push(
+                iced::time::every(player.current().display_delay()).map(|_| MyMessage::PreviewTick),
+            );
+        }
+
+        iced::Subscription::batch(subscriptions)
     }
 
-    fn new(_flags: Self::Flags) -> (Self, iced::Command<Self::Message>) {
-        let commands: Vec<iced::Command<MyMessage>> = vec![FONT_BYTES_REGULAR, FONT_BYTES_BOLD]
+    fn new(Flags { args, preferences }: Self::Flags) -> (Self, iced::Command<Self::Message>) {
+        let mut commands: Vec<iced::Command<MyMessage>> = vec![FONT_BYTES_REGULAR, FONT_BYTES_BOLD]
             .iter()
             .map(|&bytes| {
                 iced::font::load(std::borrow::Cow::from(bytes)).map(|r| {
@@ -460,7 +2566,52 @@ impl iced::Application for MyApp {
             })
             .collect();
 
-        (Default::default(), iced::Command::batch(commands))
+        // NOTE: Command-line arguments take precedence over preferences.
+        let mut app = Self {
+            // NOTE: A width given on the command line implies the width mode.
+            size_mode: if args.width.is_some() {
+                converter::SizeMode::Width
+            } else {
+                preferences.size_mode
+            },
+            gif_width: args.width.or(Some(preferences.width)),
+            gif_height: Some(preferences.height),
+            size_percent: Some(preferences.size_percent),
+            scaling: preferences.scaling,
+            gif_fps: args.fps.or(Some(preferences.fps)),
+            frame_delay: args.frame_delay.or(Some(preferences.frame_delay)),
+            trim_start: args.trim_start.map(format_seconds).unwrap_or_default(),
+            trim_end: args.trim_end.map(format_seconds).unwrap_or_default(),
+            max_colors: Some(preferences.max_colors),
+            dither: preferences.dither,
+            bayer_scale: Some(preferences.bayer_scale),
+            stats_mode: preferences.stats_mode,
+            format: args.format.unwrap_or(preferences.format),
+            target_size: preferences.target_size,
+            plays: args.plays.or(preferences.plays),
+            overlay_text: preferences.overlay_text,
+            overlay_position: preferences.overlay_position,
+            overlay_font_size: Some(preferences.overlay_font_size),
+            overlay_color: preferences.overlay_color,
+            overlay_background: preferences.overlay_background,
+            speed: preferences.speed,
+            direction: preferences.direction,
+            open_directory: preferences.open_directory,
+            save_directory: preferences.save_directory,
+            ffmpeg: diagnostics::Binary::resolve(preferences.ffmpeg_path.as_deref()),
+            window_size: preferences.window_size,
+            window_position: preferences.window_position,
+            ..Default::default()
+        };
+
+        commands.push(app.probe_ffmpeg());
+
+        if let Some(input) = args.input {
+            log::info!(target: LOG_TARGET, "Video provided on the command line: {:?}", input);
+            commands.push(app.add_paths(vec![input]));
+        }
+
+        (app, iced::Command::batch(commands))
     }
 
     fn title(&self) -> String {
@@ -496,69 +2647,376 @@ impl iced::Application for MyApp {
                 log::debug!(target: LOG_TARGET, "Save message received.");
                 self.save_to_file()
             }
+            MyMessage::Export(kind) => {
+                log::debug!(target: LOG_TARGET, "Export message received: {}", kind);
+                self.export_frames(kind)
+            }
+            MyMessage::ToggleDiagnostics => {
+                self.show_diagnostics = !self.show_diagnostics;
+                iced::Command::none()
+            }
+            MyMessage::ProbeFfmpeg => self.probe_ffmpeg(),
+            MyMessage::FfmpegProbed(report) => {
+                if report.binary != self.ffmpeg {
+                    log::debug!(target: LOG_TARGET, "Ignoring diagnostics of a replaced ffmpeg binary.");
+                    return iced::Command::none();
+                }
+                if let Some(problem) = report.problem() {
+                    log::warn!(target: LOG_TARGET, "FFmpeg is unusable: {}", problem);
+                    self.show_diagnostics = true;
+                }
+                self.diagnostics = Some(report);
+                iced::Command::none()
+            }
+            MyMessage::SelectFfmpeg => self.select_ffmpeg(),
+            MyMessage::FfmpegSelected(path) => {
+                let Some(path) = path else {
+                    return iced::Command::none();
+                };
+                log::info!(target: LOG_TARGET, "FFmpeg binary selected: {:?}", path);
+                // NOTE: The binary only replaces the current one once it has
+                // been validated.
+                iced::Command::perform(
+                    diagnostics::probe(diagnostics::Binary::custom(&path)),
+                    MyMessage::FfmpegValidated,
+                )
+            }
+            MyMessage::FfmpegValidated(report) => {
+                if let Some(problem) = report.problem() {
+                    log::warn!(target: LOG_TARGET, "Selected ffmpeg binary is unusable: {}", problem);
+                    self.notify(format!(
+                        "The selected binary ({}) cannot be used: {}",
+                        report.binary.path, problem
+                    ));
+                    return iced::Command::none();
+                }
+                self.ffmpeg = report.binary.clone();
+                self.diagnostics = Some(report);
+                // NOTE: The choice is saved right away, so that headless
+                // runs use it too, and it survives the application exiting
+                // without a close request.
+                self.save_preferences()
+            }
+            MyMessage::ResetFfmpeg => {
+                log::info!(target: LOG_TARGET, "Resetting the ffmpeg binary.");
+                self.ffmpeg = diagnostics::Binary::resolve(None);
+                iced::Command::batch([self.save_preferences(), self.probe_ffmpeg()])
+            }
+            MyMessage::PreferencesSaved(result) => {
+                if let Err(e) = result {
+                    log::warn!(target: LOG_TARGET, "Failed to save preferences: {}", e);
+                    self.notify(e);
+                }
+                iced::Command::none()
+            }
+            MyMessage::ExportResult(result) => {
+                match result {
+                    // NOTE: None here simply means that the operation was cancelled.
+                    Ok(exported) => {
+                        log::info!(target: LOG_TARGET, "Export finished: {:?}", exported)
+                    }
+                    Err(e) => {
+                        log::warn!(target: LOG_TARGET, "Failed to export frames: {:?}", e);
+                        self.notify(e);
+                    }
+                }
+                iced::Command::none()
+            }
             MyMessage::SaveResult(result) => match result {
                 Ok(saved) => {
-                    // NOTE: False here simply means that the operation was cancelled.
-                    log::info!(target: LOG_TARGET, "File saved: {}", saved);
+                    // NOTE: None here simply means that the operation was cancelled.
+                    log::info!(target: LOG_TARGET, "File saved: {:?}", saved);
+                    if let Some(directory) = saved.as_ref().and_then(|path| path.parent()) {
+                        self.save_directory = Some(directory.to_path_buf());
+                    }
                     iced::Command::none()
                 }
                 Err(e) => {
-                    // Should warn user about failure to save...
                     log::warn!(target: LOG_TARGET, "Failed to save file: {:?}", e);
+                    self.notify(e);
                     iced::Command::none()
                 }
             },
+            MyMessage::DismissNotification(id) => {
+                self.notifications
+                    .retain(|notification| notification.id != id);
+                iced::Command::none()
+            }
             MyMessage::Width(width) => {
                 self.gif_width = width;
                 log::debug!(target: LOG_TARGET, "Gif width changed: {:?}", width);
                 iced::Command::none()
             }
+            MyMessage::Height(height) => {
+                self.gif_height = height;
+                log::debug!(target: LOG_TARGET, "Gif height changed: {:?}", height);
+                iced::Command::none()
+            }
+            MyMessage::SizePercent(percent) => {
+                self.size_percent = percent;
+                log::debug!(target: LOG_TARGET, "Size percentage changed: {:?}", percent);
+                iced::Command::none()
+            }
+            MyMessage::SizeMode(mode) => {
+                self.size_mode = mode;
+                log::debug!(target: LOG_TARGET, "Size mode changed: {}", mode);
+                iced::Command::none()
+            }
+            MyMessage::Scaling(scaling) => {
+                self.scaling = scaling;
+                log::debug!(target: LOG_TARGET, "Scaling algorithm changed: {}", scaling);
+                iced::Command::none()
+            }
+            MyMessage::Rotate => {
+                self.set_transform(converter::Transform {
+                    rotation: self.transform.rotation.next(),
+                    ..self.transform
+                });
+                iced::Command::none()
+            }
+            MyMessage::FlipHorizontal => {
+                self.set_transform(converter::Transform {
+                    flip_horizontal: !self.transform.flip_horizontal,
+                    ..self.transform
+                });
+                iced::Command::none()
+            }
+            MyMessage::FlipVertical => {
+                self.set_transform(converter::Transform {
+                    flip_vertical: !self.transform.flip_vertical,
+                    ..self.transform
+                });
+                iced::Command::none()
+            }
+            MyMessage::TrimStart(start) => {
+                log::debug!(target: LOG_TARGET, "Trim start changed: {:?}", start);
+                self.trim_start = start;
+                iced::Command::none()
+            }
+            MyMessage::TrimEnd(end) => {
+                log::debug!(target: LOG_TARGET, "Trim end changed: {:?}", end);
+                self.trim_end = end;
+                iced::Command::none()
+            }
+            MyMessage::Fps(fps) => {
+                self.gif_fps = fps;
+                log::debug!(target: LOG_TARGET, "Gif frame rate changed: {:?}", fps);
+                iced::Command::none()
+            }
+            MyMessage::FrameDelay(frame_delay) => {
+                self.frame_delay = frame_delay;
+                log::debug!(target: LOG_TARGET, "Frame delay changed: {:?}", frame_delay);
+                self.update_sequence_durations();
+                iced::Command::none()
+            }
             MyMessage::ConvertMessageSentToWorker => {
-                log::debug!(target: LOG_TARGET, "Conversion task sent to worker.");
-                iced::Command::none()
-            }
-            MyMessage::Clear => {
-                if self.idle {
-                    log::info!(target: LOG_TARGET, "Clear button: no action.");
-                    iced::Command::perform(async {}, |_| MyMessage::CancelMessageSentToWorker)
-                } else if let Some(tx) = self.tx.as_ref() {
-                    log::info!(target: LOG_TARGET, "Clear button: some action.");
-                    use iced::futures::sink::SinkExt;
-                    let mut tx = tx.clone();
-                    let f = async move {
-                        let input = worker::Input::Command(ffmpeg_gif_maker::Command::Cancel);
-                        tx.send(input).await
-                    };
-                    // Should I be ignoring `send` errors here?
-                    log::debug!(target: LOG_TARGET, "Dispatching command to send cancellation request to worker...");
-                    iced::Command::perform(f, |_| MyMessage::CancelMessageSentToWorker)
-                } else {
+                log::debug!(target: LOG_TARGET, "Conversion tasks sent to worker.");
+                iced::Command::none()
+            }
+            MyMessage::Clear => match self.selected_job {
+                Some(id) => self.remove_job(id),
+                None => {
                     log::debug!(target: LOG_TARGET, "Nothing to clear.");
                     iced::Command::none()
                 }
+            },
+            MyMessage::RemoveJob(id) => self.remove_job(id),
+            MyMessage::SelectJob(id) => {
+                log::debug!(target: LOG_TARGET, "Job selected: {}", id);
+                self.selected_job = Some(id);
+                iced::Command::none()
+            }
+            MyMessage::StillLoaded(id, result) => {
+                let transform = self.transform;
+                let Some(job) = self.job_mut(id) else {
+                    log::debug!(target: LOG_TARGET, "Ignoring video preview for removed job: {}", id);
+                    return iced::Command::none();
+                };
+                match result {
+                    Ok(frame) => {
+                        log::debug!(target: LOG_TARGET, "Video preview loaded ({}x{}, duration: {:?})", frame.width, frame.height, frame.video_duration);
+                        // NOTE: An image sequence's duration depends on the
+                        // frame delay instead.
+                        if job.images.is_none() {
+                            job.video_duration = frame.video_duration;
+                        }
+                        job.still =
+                            Some(job::Still::new(frame.png_data, frame.width, frame.height));
+                        job.orient_still(transform);
+                        job.status = job::Status::Pending;
+                    }
+                    Err(e) => {
+                        log::warn!(target: LOG_TARGET, "Failed to load video preview: {:?}", e);
+                        self.fail_job(
+                            id,
+                            match e {
+                                converter::Error::EmptyStdout => {
+                                    "Likely unsupported file format.".into()
+                                }
+                                e @ _ => e.to_string(),
+                            },
+                        );
+                    }
+                }
+                iced::Command::none()
+            }
+            MyMessage::MetadataLoaded(id, result) => {
+                let Some(job) = self.job_mut(id) else {
+                    log::debug!(target: LOG_TARGET, "Ignoring metadata for removed job: {}", id);
+                    return iced::Command::none();
+                };
+                match result {
+                    Ok(metadata) => job.metadata = Some(metadata),
+                    Err(e) => {
+                        log::warn!(target: LOG_TARGET, "Failed to probe video metadata: {}", e)
+                    }
+                }
+                iced::Command::none()
+            }
+            MyMessage::ApplySuggestedSettings => {
+                let Some(metadata) = self.selected_job().and_then(|job| job.metadata.clone())
+                else {
+                    return iced::Command::none();
+                };
+                log::debug!(target: LOG_TARGET, "Applying suggested settings: {:?}, {:?}", metadata.suggested_width(), metadata.suggested_fps());
+                if let Some(width) = metadata.suggested_width() {
+                    self.size_mode = converter::SizeMode::Width;
+                    self.gif_width = Some(width);
+                }
+                if let Some(fps) = metadata.suggested_fps() {
+                    self.gif_fps = Some(fps.min(MAX_GIF_FPS));
+                }
+                iced::Command::none()
+            }
+            MyMessage::Crop(crop) => {
+                log::debug!(target: LOG_TARGET, "Crop changed: {:?}", crop);
+                let transform = self.transform;
+                if let Some(job) = self.selected_job.and_then(|id| self.job_mut(id)) {
+                    job.crop = crop;
+                    job.orient_still(transform);
+                }
+                iced::Command::none()
+            }
+            MyMessage::ToggleSettings => {
+                self.show_settings = !self.show_settings;
+                iced::Command::none()
+            }
+            MyMessage::MaxColors(max_colors) => {
+                self.max_colors = max_colors;
+                log::debug!(target: LOG_TARGET, "Max colors changed: {:?}", max_colors);
+                iced::Command::none()
+            }
+            MyMessage::Dither(dither) => {
+                self.dither = dither;
+                log::debug!(target: LOG_TARGET, "Dither changed: {:?}", dither);
+                iced::Command::none()
+            }
+            MyMessage::BayerScale(bayer_scale) => {
+                self.bayer_scale = bayer_scale;
+                log::debug!(target: LOG_TARGET, "Bayer scale changed: {:?}", bayer_scale);
+                iced::Command::none()
+            }
+            MyMessage::StatsMode(stats_mode) => {
+                self.stats_mode = stats_mode;
+                log::debug!(target: LOG_TARGET, "Stats mode changed: {:?}", stats_mode);
+                iced::Command::none()
+            }
+            MyMessage::Speed(speed) => {
+                self.speed = speed;
+                log::debug!(target: LOG_TARGET, "Speed changed: {:?}", speed);
+                iced::Command::none()
+            }
+            MyMessage::Direction(direction) => {
+                self.direction = direction;
+                log::debug!(target: LOG_TARGET, "Direction changed: {:?}", direction);
+                iced::Command::none()
+            }
+            MyMessage::Plays(plays) => {
+                self.plays = plays;
+                log::debug!(target: LOG_TARGET, "Plays changed: {:?}", plays);
+                iced::Command::none()
+            }
+            MyMessage::OverlayText(text) => {
+                self.overlay_text = text;
+                iced::Command::none()
+            }
+            MyMessage::OverlayPosition(position) => {
+                self.overlay_position = position;
+                log::debug!(target: LOG_TARGET, "Overlay position changed: {:?}", position);
+                iced::Command::none()
+            }
+            MyMessage::OverlayFontSize(font_size) => {
+                self.overlay_font_size = font_size;
+                log::debug!(target: LOG_TARGET, "Overlay font size changed: {:?}", font_size);
+                iced::Command::none()
+            }
+            MyMessage::OverlayColor(color) => {
+                self.overlay_color = color;
+                log::debug!(target: LOG_TARGET, "Overlay color changed: {:?}", color);
+                iced::Command::none()
+            }
+            MyMessage::OverlayBackground(background) => {
+                self.overlay_background = background;
+                log::debug!(target: LOG_TARGET, "Overlay background changed: {:?}", background);
+                iced::Command::none()
+            }
+            MyMessage::TargetSize(target_size) => {
+                self.target_size = target_size;
+                log::debug!(target: LOG_TARGET, "Target size changed: {:?}", target_size);
+                iced::Command::none()
+            }
+            MyMessage::Format(format) => {
+                self.format = format;
+                log::debug!(target: LOG_TARGET, "Output format changed: {:?}", format);
+                iced::Command::none()
+            }
+            MyMessage::Convert => {
+                log::debug!(target: LOG_TARGET, "Convert message received.");
+                if !self.is_ffmpeg_usable() {
+                    log::debug!(target: LOG_TARGET, "Conversion ignored because ffmpeg is unusable.");
+                    return iced::Command::none();
+                }
+                self.new_tasks()
             }
             MyMessage::SelectFile => {
                 log::debug!(target: LOG_TARGET, "Received message requesting file selection. Calling command generator method...");
                 self.select_file()
             }
-            MyMessage::FileSelected(path) => {
-                log::info!(target: LOG_TARGET, "File selected: {:?}", path);
-                if let Some(path) = path {
-                    self.new_task(path)
-                } else {
-                    iced::Command::none()
+            MyMessage::SelectFolder => {
+                log::debug!(target: LOG_TARGET, "Received message requesting folder selection. Calling command generator method...");
+                self.select_folder()
+            }
+            MyMessage::FilesSelected(paths) => {
+                log::info!(target: LOG_TARGET, "Files selected: {:?}", paths);
+                if let Some(directory) = paths.first().and_then(|path| path.parent()) {
+                    self.open_directory = Some(directory.to_path_buf());
                 }
+                self.add_paths(paths)
             }
             MyMessage::Event(event) => {
                 match event {
                     iced::Event::Window(w) => match w {
                         iced::window::Event::FileDropped(path) => {
-                            if self.is_working() {
-                                log::info!(target: LOG_TARGET, "File dropped on application window, but already working, so file will be ignored. File path: {:?}", path);
+                            log::info!(target: LOG_TARGET, "File dropped on application window: {:?}", path);
+                            if !self.is_ffmpeg_usable() {
+                                log::warn!(target: LOG_TARGET, "Ignoring dropped file, since FFmpeg is unusable.");
                                 return iced::Command::none();
                             }
-                            log::info!(target: LOG_TARGET, "File dropped on application window: {:?}", path);
-                            return self.new_task(path);
+                            if is_image_file(&path) {
+                                return self.add_dropped_image(path);
+                            }
+                            return self.add_paths(vec![path]);
+                        }
+                        iced::window::Event::Resized { width, height } => {
+                            self.window_size = (width, height);
+                        }
+                        iced::window::Event::Moved { x, y } => {
+                            self.window_position = Some((x, y));
+                        }
+                        iced::window::Event::CloseRequested => {
+                            log::info!(target: LOG_TARGET, "Close requested, so saving preferences before exiting...");
+                            self.preferences().save();
+                            return iced::window::close();
                         }
                         _ => {}
                     },
@@ -568,101 +3026,279 @@ impl iced::Application for MyApp {
             }
             MyMessage::CancelMessageSentToWorker => {
                 log::info!(target: LOG_TARGET, "Cancel command sent to worker");
-                if self.idle {
-                    self.clear_all();
+                iced::Command::none()
+            }
+            MyMessage::PlayPause => {
+                if let Some(player) = self.selected_player_mut() {
+                    player.toggle();
+                }
+                iced::Command::none()
+            }
+            MyMessage::StepFrame(offset) => {
+                if let Some(player) = self.selected_player_mut() {
+                    player.step(offset);
                 }
                 iced::Command::none()
             }
-            MyMessage::GifFramesLoaded(result) => {
-                // NOTE: Ideally, the GIF processing job could be cancelled
-                // if not ready when clearing, to avoid populating `error_messsage` upon error
-                // or `frames` when the job has been cancelled or cleared.
-                // For now we take care of "Ok", but not "Err".
-                log::debug!(target: LOG_TARGET, "Animated GIF 'frames loaded' message recevied.");
+            MyMessage::SeekFrame(index) => {
+                if let Some(player) = self.selected_player_mut() {
+                    player.seek(index as usize);
+                }
+                iced::Command::none()
+            }
+            MyMessage::PreviewTick => {
+                if let Some(player) = self.selected_player_mut() {
+                    player.tick();
+                }
+                iced::Command::none()
+            }
+            MyMessage::InputFramesLoaded(id, result) => {
+                let Some(job) = self.job_mut(id) else {
+                    log::debug!(target: LOG_TARGET, "Ignoring input GIF frames for removed job: {}", id);
+                    return iced::Command::none();
+                };
+                job.input_decoding = None;
+                match result {
+                    Ok(frames) => {
+                        log::debug!(target: LOG_TARGET, "Input GIF frames loaded for job {}.", id);
+                        job.input_player =
+                            Some(preview::Player::new(frames, converter::Loop::Forever));
+                    }
+                    // NOTE: The job can still be converted, and its still
+                    // frame gets displayed instead.
+                    Err(e) => {
+                        log::warn!(target: LOG_TARGET, "Failed to decode input GIF for job {}: {:?}", id, e)
+                    }
+                }
+                iced::Command::none()
+            }
+            MyMessage::ToggleFrameEditor => {
+                self.show_frame_editor = !self.show_frame_editor;
+                iced::Command::none()
+            }
+            MyMessage::DeleteFrame => {
+                self.edit_frames(|player| player.remove_current());
+                iced::Command::none()
+            }
+            MyMessage::DuplicateFrame => {
+                self.edit_frames(|player| player.duplicate_current());
+                iced::Command::none()
+            }
+            MyMessage::EditFrameDelay(delay) => {
+                if let Some(delay) = delay {
+                    self.edit_frames(|player| {
+                        player.set_current_delay(std::time::Duration::from_millis(delay as u64))
+                    });
+                }
+                iced::Command::none()
+            }
+            MyMessage::ApplyFrameEdits => self.apply_frame_edits(),
+            MyMessage::FramesEncoded(id, result) => {
+                let Some(job) = self
+                    .job_mut(id)
+                    .filter(|job| job.status == job::Status::Encoding)
+                else {
+                    log::debug!(target: LOG_TARGET, "Ignoring encoded frames for removed job: {}", id);
+                    return iced::Command::none();
+                };
+                job.status = job::Status::Done;
+                match result {
+                    Ok(data) => {
+                        log::debug!(target: LOG_TARGET, "Edited frames of job {} encoded.", id);
+                        let notification = job
+                            .search
+                            .as_ref()
+                            .filter(|search| data.len() > search.budget())
+                            .map(|search| {
+                                format!(
+                                    "The edited {:?} ({}) no longer fits within {}.",
+                                    job.file_name(),
+                                    format_size(data.len()),
+                                    target_size::format_budget(search.budget())
+                                )
+                            });
+                        job.image_data = Some(data);
+                        job.frames_edited = false;
+                        if let Some(notification) = notification {
+                            self.notify(notification);
+                        }
+                    }
+                    // NOTE: The previous output is kept, along with the
+                    // edits, so that applying them can be retried.
+                    Err(e) => {
+                        log::warn!(target: LOG_TARGET, "Failed to encode edited frames: {:?}", e);
+                        self.notify(e.to_string());
+                    }
+                }
+                iced::Command::none()
+            }
+            MyMessage::EditAgain => {
+                if let Some(id) = self.selected_job {
+                    self.edit_again(id);
+                }
+                iced::Command::none()
+            }
+            MyMessage::GifFramesLoaded(id, result) => {
+                log::debug!(target: LOG_TARGET, "Animated GIF 'frames loaded' message recevied for job {}.", id);
+                let Some(job) = self
+                    .job_mut(id)
+                    .filter(|job| job.status == job::Status::Decoding)
+                else {
+                    log::warn!(target: LOG_TARGET, "Received GIF frames but the job is no longer decoding, so assuming it has been cleared and ignoring the result.");
+                    return iced::Command::none();
+                };
+                job.decoding = None;
                 match result {
                     Err(e) => {
                         log::warn!(target: LOG_TARGET, "Error preparing GIF frames: {:?}", e);
-                        self.error_message = Some(e.to_string());
+                        self.fail_job(id, e.to_string());
                     }
                     Ok(frames) => {
-                        if self.image_data.is_some() {
-                            self.frames = Some(frames);
-                        } else {
-                            log::warn!(target: LOG_TARGET, "Received GIF frames but image data is no longer there, so assuming the job has been cleared and ignoring the result.");
-                        }
+                        job.player = Some(preview::Player::new(frames, job.looping));
+                        job.status = job::Status::Done;
                     }
                 }
                 iced::Command::none()
             }
             MyMessage::WorkerEvent(event) => match event {
-                worker::Event::CommandRefused(refused_command) => {
-                    log::error!(target: LOG_TARGET, "Command was refused by worker: {:?}", refused_command);
-                    panic!();
-                }
-                worker::Event::TaskRefused(refused_task_settings) => {
-                    log::error!(target: LOG_TARGET, "New task was refused by worker: {:?}", refused_task_settings);
-                    panic!();
+                worker::Event::CommandRefused(id, refused_command) => {
+                    // NOTE: This can happen if a job completes right before
+                    // the user tries to cancel it.
+                    log::warn!(target: LOG_TARGET, "Command for job {} was refused by worker: {:?}", id, refused_command);
+                    iced::Command::none()
                 }
                 worker::Event::Ready(tx) => {
                     log::info!(target: LOG_TARGET, "Worker is ready (received 'command sender' channel)");
                     self.tx = Some(tx);
                     iced::Command::none()
                 }
-                worker::Event::Message(message) => match message {
-                    ffmpeg_gif_maker::Message::Done => {
-                        // IMPORTANT: Rely on this message instead of 'success' or 'error' to mark the job as completed.
-                        log::info!(target: LOG_TARGET, "'Done' message received.");
-                        self.idle = true;
-                        let Some(image_data) = self.image_data.as_ref() else {
-                            // NOTE: This edge case could be eliminated by introducing
-                            // a cancellation channel in the `gif::Frames::from_bytes`
-                            // method.
-                            log::warn!(target: LOG_TARGET, "There was no image data, so not requesting GIF preview.");
-                            return iced::Command::none();
-                        };
-                        let data = image_data.clone();
-                        log::debug!(target: LOG_TARGET, "Returning command that will initiate the GIF processing...");
-                        return iced::Command::perform(
-                            iced_gif::gif::Frames::from_bytes(data),
-                            MyMessage::GifFramesLoaded,
-                        );
-                    }
-                    ffmpeg_gif_maker::Message::Success(image_data) => {
-                        log::debug!(target: LOG_TARGET, "Image data received from worker.");
-                        self.image_data = Some(image_data);
-                        iced::Command::none()
-                    }
-                    ffmpeg_gif_maker::Message::VideoDuration(duration) => {
-                        log::debug!(target: LOG_TARGET, "Video duration received from worker: {:?}", duration);
-                        self.video_duration = Some(duration);
-                        iced::Command::none()
+                worker::Event::Started(id) => {
+                    log::info!(target: LOG_TARGET, "Worker started job {}.", id);
+                    if let Some(job) = self.job_mut(id) {
+                        job.status = job::Status::Converting;
                     }
-                    ffmpeg_gif_maker::Message::Progress(progress) => {
-                        log::debug!(target: LOG_TARGET, "Progress received from worker: {:.2}", progress);
-                        self.progress = Some(progress);
-                        iced::Command::none()
-                    }
-                    ffmpeg_gif_maker::Message::Error(error) => {
-                        log::warn!(target: LOG_TARGET, "Error received from worker: {:?}", error);
-                        match error {
-                            ffmpeg_gif_maker::Error::Cancelled => {
-                                self.clear_all();
+                    iced::Command::none()
+                }
+                worker::Event::Message(id, message) => {
+                    let Some(job) = self.job_mut(id) else {
+                        log::warn!(target: LOG_TARGET, "Ignoring worker message for unknown job {}: {:?}", id, message);
+                        return iced::Command::none();
+                    };
+                    match message {
+                        converter::Message::Done => {
+                            // IMPORTANT: Rely on this message instead of 'success' or 'error' to mark the job as completed.
+                            log::info!(target: LOG_TARGET, "'Done' message received for job {}.", id);
+                            if job.status != job::Status::Converting {
+                                return iced::Command::none();
+                            }
+                            if job.image_data.is_none() {
+                                log::warn!(target: LOG_TARGET, "There was no image data, so not requesting GIF preview.");
+                                return iced::Command::none();
+                            }
+                            let mut notification = None;
+                            if let (Some(search), Some(data)) =
+                                (job.search.as_mut(), job.image_data.take())
+                            {
+                                match search.record(data) {
+                                    target_size::Outcome::Retry(settings) => {
+                                        job.status = job::Status::Queued;
+                                        job.progress = None;
+                                        return self.send_inputs(vec![worker::Input::NewTask(
+                                            id, settings,
+                                        )]);
+                                    }
+                                    target_size::Outcome::Exhausted(data) => {
+                                        notification = Some(format!(
+                                            "No attempt for {:?} fits within {}, so keeping the smallest one ({}).",
+                                            job.file_name(),
+                                            target_size::format_budget(search.budget()),
+                                            format_size(data.len())
+                                        ));
+                                        job.image_data = Some(data);
+                                    }
+                                    target_size::Outcome::Fits(data) => {
+                                        job.image_data = Some(data);
+                                    }
+                                }
                             }
-                            ffmpeg_gif_maker::Error::EmptyStdout => {
-                                self.error_message = Some("Likely unsupported file format.".into());
+                            if let Some(notification) = notification {
+                                self.notify(notification);
                             }
-                            e @ _ => {
-                                self.error_message = Some(e.to_string());
+                            self.finish_job(id)
+                        }
+                        converter::Message::Success(image_data) => {
+                            log::debug!(target: LOG_TARGET, "Image data received from worker.");
+                            job.image_data = Some(image_data);
+                            iced::Command::none()
+                        }
+                        converter::Message::VideoDuration(duration) => {
+                            log::debug!(target: LOG_TARGET, "Video duration received from worker: {:?}", duration);
+                            job.video_duration = Some(duration);
+                            iced::Command::none()
+                        }
+                        converter::Message::Progress(progress) => {
+                            log::debug!(target: LOG_TARGET, "Progress received from worker: {:.2}", progress);
+                            job.progress = Some(progress);
+                            iced::Command::none()
+                        }
+                        converter::Message::Error(error) => {
+                            log::warn!(target: LOG_TARGET, "Error received from worker: {:?}", error);
+                            match error {
+                                converter::Error::Cancelled => {
+                                    self.discard_job(id);
+                                }
+                                converter::Error::EmptyStdout => {
+                                    self.fail_job(id, "Likely unsupported file format.".into());
+                                }
+                                e @ _ => {
+                                    self.fail_job(id, e.to_string());
+                                }
                             }
+                            iced::Command::none()
                         }
-                        iced::Command::none()
                     }
-                },
+                }
             },
         }
     }
 }
 
+fn format_duration(duration: std::time::Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            (seconds % 3600) / 60,
+            seconds % 60
+        )
+    } else {
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// Formats the duration in seconds, as accepted by the trim inputs.
+fn format_seconds(duration: std::time::Duration) -> String {
+    format!("{}", (duration.as_secs_f64() * 1000.0).round() / 1000.0)
+}
+
+fn format_size(bytes: usize) -> String {
+    const KIB: f64 = 1024.0;
+    const MIB: f64 = KIB * 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= MIB {
+        format!("{:.2} MiB", bytes / MIB)
+    } else if bytes >= KIB {
+        format!("{:.1} KiB", bytes / KIB)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+fn format_timestamp(duration: std::time::Duration) -> String {
+    format!("{:.2}s", duration.as_secs_f64())
+}
+
 mod numeric_input {
     // [component example](https://github.com/iced-rs/iced/blob/master/examples/component/src/main.rs)
 
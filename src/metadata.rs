@@ -0,0 +1,257 @@
+const LOG_TARGET: &'static str = "iced_gif_maker::metadata";
+
+/// Frame rates that differ by more than this (in FPS) are considered
+/// different, since container-reported rates are often slightly off.
+const FRAME_RATE_TOLERANCE: f64 = 0.5;
+/// The widest a suggested output is, along with its tallest.
+const SUGGESTED_MAX_SIZE: u32 = 480;
+const SUGGESTED_MAX_FPS: f64 = 15.0;
+
+/// What `ffprobe` reports about a video's first video stream.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metadata {
+    /// The stored frame size, before applying the rotation.
+    pub width: u32,
+    pub height: u32,
+    pub codec: Option<String>,
+    /// The stream's base frame rate (i.e. `r_frame_rate`).
+    pub frame_rate: Option<f64>,
+    pub average_frame_rate: Option<f64>,
+    /// The rotation (in degrees) that players, and FFmpeg, apply to the
+    /// stored frames.
+    pub rotation: i32,
+    pub duration: Option<std::time::Duration>,
+}
+
+impl Metadata {
+    /// The frame size once rotated, which is what the conversion scales.
+    pub fn display_size(&self) -> (u32, u32) {
+        if self.rotation.rem_euclid(180) == 90 {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        }
+    }
+
+    pub fn is_portrait(&self) -> bool {
+        let (width, height) = self.display_size();
+        height > width
+    }
+
+    /// Whether frames are not evenly spaced (e.g. phone or screen
+    /// recordings), in which case the output's timing may look uneven.
+    pub fn is_variable_frame_rate(&self) -> bool {
+        match (self.frame_rate, self.average_frame_rate) {
+            (Some(rate), Some(average)) => (rate - average).abs() > FRAME_RATE_TOLERANCE,
+            _ => false,
+        }
+    }
+
+    /// A width fitting the output within `SUGGESTED_MAX_SIZE` pixels (in
+    /// both directions), without upscaling.
+    pub fn suggested_width(&self) -> Option<u16> {
+        let (width, height) = self.display_size();
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let scale = (SUGGESTED_MAX_SIZE as f64 / width.max(height) as f64).min(1.0);
+        // NOTE: Even widths are kept, since some encoders require them.
+        let suggested = ((width as f64 * scale / 2.0).round() as u32 * 2).max(2);
+        u16::try_from(suggested).ok()
+    }
+
+    /// A frame rate dividing the source's, so that frames are dropped
+    /// evenly, and not above `SUGGESTED_MAX_FPS`.
+    pub fn suggested_fps(&self) -> Option<u8> {
+        let rate = self.average_frame_rate.or(self.frame_rate)?;
+        if rate <= 0.0 {
+            return None;
+        }
+        let divisor = (rate / SUGGESTED_MAX_FPS).ceil().max(1.0);
+        Some((rate / divisor).round().clamp(1.0, u8::MAX as f64) as u8)
+    }
+}
+
+impl std::fmt::Display for Metadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (width, height) = self.display_size();
+        write!(f, "{}x{} px", width, height)?;
+        if let Some(rate) = self.average_frame_rate.or(self.frame_rate) {
+            write!(f, " | {:.2} FPS", rate)?;
+        }
+        if let Some(codec) = self.codec.as_ref() {
+            write!(f, " | {}", codec)?;
+        }
+        if self.rotation != 0 {
+            write!(f, " | rotated {}° (applied automatically)", self.rotation)?;
+        }
+        if let Some(duration) = self.duration {
+            write!(f, " | {:.1} s", duration.as_secs_f64())?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs `ffprobe` on the input's first video stream.
+pub async fn probe(ffprobe_path: String, input_path: String) -> Result<Metadata, String> {
+    log::debug!(target: LOG_TARGET, "Probing {:?} using {}", input_path, ffprobe_path);
+
+    let output = tokio::process::Command::new(&ffprobe_path)
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-show_entries",
+            "stream=width,height,codec_name,r_frame_rate,avg_frame_rate:stream_tags=rotate:stream_side_data=rotation:format=duration",
+            "-of",
+            "default=noprint_wrappers=1",
+        ])
+        .arg(&input_path)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to start ffprobe: {}", e))?;
+
+    if !output.status.success() {
+        let log = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("ffprobe failed: {}", log.trim()));
+    }
+
+    let metadata = parse(&String::from_utf8_lossy(&output.stdout));
+    log::debug!(target: LOG_TARGET, "Metadata of {:?}: {:?}", input_path, metadata);
+    if metadata.width == 0 || metadata.height == 0 {
+        return Err("ffprobe did not find any video stream.".into());
+    }
+    Ok(metadata)
+}
+
+/// Parses `key=value` lines, as printed by ffprobe's default writer.
+fn parse(output: &str) -> Metadata {
+    let mut metadata = Metadata::default();
+    for (key, value) in output
+        .lines()
+        .filter_map(|line| line.trim().split_once('='))
+    {
+        match key {
+            "width" => metadata.width = value.parse().unwrap_or_default(),
+            "height" => metadata.height = value.parse().unwrap_or_default(),
+            "codec_name" => metadata.codec = Some(value.into()),
+            "r_frame_rate" => metadata.frame_rate = parse_rate(value),
+            "avg_frame_rate" => metadata.average_frame_rate = parse_rate(value),
+            // NOTE: The display matrix's rotation is counterclockwise, while
+            // the (older) `rotate` tag is clockwise.
+            "rotation" => {
+                if let Ok(rotation) = value.parse::<f64>() {
+                    metadata.rotation = (-rotation.round() as i32).rem_euclid(360);
+                }
+            }
+            "TAG:rotate" => {
+                if let Ok(rotation) = value.parse::<i32>() {
+                    metadata.rotation = rotation.rem_euclid(360);
+                }
+            }
+            "duration" => {
+                metadata.duration = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|duration| duration.is_finite() && *duration >= 0.0)
+                    .map(std::time::Duration::from_secs_f64)
+            }
+            _ => {}
+        }
+    }
+    metadata
+}
+
+/// Parses rates such as `30000/1001`, where `0/0` means unknown.
+fn parse_rate(value: &str) -> Option<f64> {
+    let (numerator, denominator) = value.split_once('/')?;
+    let numerator: f64 = numerator.parse().ok()?;
+    let denominator: f64 = denominator.parse().ok()?;
+    if numerator <= 0.0 || denominator <= 0.0 {
+        return None;
+    }
+    Some(numerator / denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ffprobe_output() {
+        let metadata = parse(
+            "width=1920\n\
+             height=1080\n\
+             codec_name=h264\n\
+             r_frame_rate=30000/1001\n\
+             avg_frame_rate=24/1\n\
+             rotation=-90\n\
+             duration=12.500000\n",
+        );
+        assert_eq!(metadata.width, 1920);
+        assert_eq!(metadata.height, 1080);
+        assert_eq!(metadata.codec.as_deref(), Some("h264"));
+        assert_eq!(metadata.average_frame_rate, Some(24.0));
+        assert_eq!(metadata.rotation, 90);
+        assert_eq!(
+            metadata.duration,
+            Some(std::time::Duration::from_millis(12500))
+        );
+        assert_eq!(metadata.display_size(), (1080, 1920));
+        assert!(metadata.is_portrait());
+        assert!(metadata.is_variable_frame_rate());
+    }
+
+    #[test]
+    fn parses_rotate_tags_and_unknown_values() {
+        let metadata = parse("TAG:rotate=-90\nduration=N/A\nr_frame_rate=0/0\nbogus\n");
+        assert_eq!(metadata.rotation, 270);
+        assert_eq!(metadata.duration, None);
+        assert_eq!(metadata.frame_rate, None);
+        assert!(!metadata.is_variable_frame_rate());
+    }
+
+    #[test]
+    fn parses_rates() {
+        assert_eq!(parse_rate("25/1"), Some(25.0));
+        assert!((parse_rate("30000/1001").unwrap() - 29.97).abs() < 0.01);
+        assert_eq!(parse_rate("0/0"), None);
+        assert_eq!(parse_rate("25"), None);
+    }
+
+    #[test]
+    fn suggests_settings() {
+        let metadata = Metadata {
+            width: 1920,
+            height: 1080,
+            frame_rate: Some(30000.0 / 1001.0),
+            ..Default::default()
+        };
+        assert_eq!(metadata.suggested_width(), Some(480));
+        assert_eq!(metadata.suggested_fps(), Some(15));
+
+        let metadata = Metadata {
+            width: 1920,
+            height: 1080,
+            rotation: 90,
+            average_frame_rate: Some(60.0),
+            ..Default::default()
+        };
+        assert_eq!(metadata.suggested_width(), Some(270));
+        assert_eq!(metadata.suggested_fps(), Some(15));
+
+        let metadata = Metadata {
+            width: 320,
+            height: 240,
+            frame_rate: Some(12.0),
+            ..Default::default()
+        };
+        assert_eq!(metadata.suggested_width(), Some(320));
+        assert_eq!(metadata.suggested_fps(), Some(12));
+        assert_eq!(Metadata::default().suggested_width(), None);
+        assert_eq!(Metadata::default().suggested_fps(), None);
+    }
+}
@@ -0,0 +1,402 @@
+const LOG_TARGET: &'static str = "iced_gif_maker::overlay";
+
+/// Where the bundled font is written (under the user's cache directory), so
+/// that FFmpeg's `drawtext` filter can use it.
+const FONT_DIRECTORY_NAME: &'static str = "iced_gif_maker";
+const FONT_FILE_NAME: &'static str = "Roboto-Bold.ttf";
+const BACKGROUND_OPACITY: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Position {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+    /// Centered text, on a band spanning the frame's width.
+    TopBanner,
+    BottomBanner,
+}
+
+impl Position {
+    pub const ALL: [Position; 6] = [
+        Position::TopLeft,
+        Position::TopRight,
+        Position::BottomLeft,
+        Position::BottomRight,
+        Position::TopBanner,
+        Position::BottomBanner,
+    ];
+
+    fn is_banner(&self) -> bool {
+        matches!(self, Position::TopBanner | Position::BottomBanner)
+    }
+
+    fn is_top(&self) -> bool {
+        matches!(
+            self,
+            Position::TopLeft | Position::TopRight | Position::TopBanner
+        )
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Position::TopLeft => write!(f, "Top left"),
+            Position::TopRight => write!(f, "Top right"),
+            Position::BottomLeft => write!(f, "Bottom left"),
+            Position::BottomRight => write!(f, "Bottom right"),
+            Position::TopBanner => write!(f, "Top banner"),
+            Position::BottomBanner => write!(f, "Bottom banner"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Color {
+    #[default]
+    White,
+    Black,
+    Yellow,
+    Red,
+}
+
+impl Color {
+    pub const ALL: [Color; 4] = [Color::White, Color::Black, Color::Yellow, Color::Red];
+
+    fn ffmpeg_name(&self) -> &'static str {
+        match self {
+            Color::White => "white",
+            Color::Black => "black",
+            Color::Yellow => "yellow",
+            Color::Red => "red",
+        }
+    }
+
+    pub fn to_iced(&self) -> iced::Color {
+        match self {
+            Color::White => iced::Color::WHITE,
+            Color::Black => iced::Color::BLACK,
+            Color::Yellow => iced::Color::from_rgb8(255, 255, 0),
+            Color::Red => iced::Color::from_rgb8(255, 0, 0),
+        }
+    }
+
+    /// The background box's color, which contrasts with the text.
+    fn background(&self) -> Color {
+        match self {
+            Color::Black => Color::White,
+            Color::White | Color::Yellow | Color::Red => Color::Black,
+        }
+    }
+}
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Color::White => write!(f, "White"),
+            Color::Black => write!(f, "Black"),
+            Color::Yellow => write!(f, "Yellow"),
+            Color::Red => write!(f, "Red"),
+        }
+    }
+}
+
+/// A caption (or watermark) burnt into the output. Its font size is in
+/// output pixels, so that it does not depend on the video's resolution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Overlay {
+    pub text: String,
+    pub position: Position,
+    pub font_size: u16,
+    pub color: Color,
+    pub background: bool,
+}
+
+impl Overlay {
+    pub const DEFAULT_FONT_SIZE: u16 = 20;
+
+    /// The space between the text and the frame's edges (or the banner's).
+    fn margin(&self) -> u16 {
+        self.font_size / 2
+    }
+
+    /// The space between the text and its background box's edges.
+    fn padding(&self) -> u16 {
+        (self.font_size / 4).max(1)
+    }
+
+    fn banner_height(&self) -> u16 {
+        self.font_size + 2 * self.margin()
+    }
+
+    /// Returns the FFmpeg filters drawing the overlay, which must be applied
+    /// once the frames have been scaled to the output's size.
+    pub fn filter(&self) -> String {
+        let margin = self.margin();
+        let banner_height = self.banner_height();
+        let background = format!(
+            "{}@{}",
+            self.color.background().ffmpeg_name(),
+            BACKGROUND_OPACITY
+        );
+
+        let (x, y) = match self.position {
+            Position::TopLeft => (format!("{}", margin), format!("{}", margin)),
+            Position::TopRight => (format!("w-tw-{}", margin), format!("{}", margin)),
+            Position::BottomLeft => (format!("{}", margin), format!("h-th-{}", margin)),
+            Position::BottomRight => (format!("w-tw-{}", margin), format!("h-th-{}", margin)),
+            Position::TopBanner => ("(w-tw)/2".into(), format!("({}-th)/2", banner_height)),
+            Position::BottomBanner => (
+                "(w-tw)/2".into(),
+                format!("h-{}+({}-th)/2", banner_height, banner_height),
+            ),
+        };
+
+        let mut drawtext = format!(
+            "drawtext=fontfile={}:text={}:expansion=none:fontsize={}:fontcolor={}:x={}:y={}",
+            escape(&font_path().to_string_lossy()),
+            escape(&self.text),
+            self.font_size,
+            self.color.ffmpeg_name(),
+            x,
+            y
+        );
+        if !self.background {
+            return drawtext;
+        }
+
+        if !self.position.is_banner() {
+            drawtext.push_str(&format!(
+                ":box=1:boxcolor={}:boxborderw={}",
+                background,
+                self.padding()
+            ));
+            return drawtext;
+        }
+
+        let banner_y = if self.position.is_top() {
+            "0".into()
+        } else {
+            format!("ih-{}", banner_height)
+        };
+        format!(
+            "drawbox=x=0:y={}:w=iw:h={}:color={}:t=fill,{}",
+            banner_y, banner_height, background, drawtext
+        )
+    }
+
+    /// Lays the overlay out on a frame of the given size, for previewing it.
+    /// Returns the text's top left corner, along with its background's
+    /// bounds (if any), using the same units as the sizes.
+    pub fn layout(
+        &self,
+        frame: iced::Size,
+        text: iced::Size,
+    ) -> (iced::Point, Option<iced::Rectangle>) {
+        let margin = self.margin() as f32;
+        let padding = self.padding() as f32;
+        let banner_height = self.banner_height() as f32;
+
+        let x = match self.position {
+            Position::TopLeft | Position::BottomLeft => margin,
+            Position::TopRight | Position::BottomRight => frame.width - text.width - margin,
+            Position::TopBanner | Position::BottomBanner => (frame.width - text.width) / 2.0,
+        };
+        let y = match self.position {
+            Position::TopLeft | Position::TopRight => margin,
+            Position::BottomLeft | Position::BottomRight => frame.height - text.height - margin,
+            Position::TopBanner => (banner_height - text.height) / 2.0,
+            Position::BottomBanner => {
+                frame.height - banner_height + (banner_height - text.height) / 2.0
+            }
+        };
+        let origin = iced::Point::new(x, y);
+
+        if !self.background {
+            return (origin, None);
+        }
+
+        let background = if self.position.is_banner() {
+            iced::Rectangle {
+                x: 0.0,
+                y: if self.position.is_top() {
+                    0.0
+                } else {
+                    frame.height - banner_height
+                },
+                width: frame.width,
+                height: banner_height,
+            }
+        } else {
+            iced::Rectangle {
+                x: x - padding,
+                y: y - padding,
+                width: text.width + 2.0 * padding,
+                height: text.height + 2.0 * padding,
+            }
+        };
+        (origin, Some(background))
+    }
+
+    pub fn background_color(&self) -> iced::Color {
+        iced::Color {
+            a: BACKGROUND_OPACITY,
+            ..self.color.background().to_iced()
+        }
+    }
+}
+
+fn cache_directory() -> Option<std::path::PathBuf> {
+    if let Some(directory) = std::env::var_os("XDG_CACHE_HOME").filter(|d| !d.is_empty()) {
+        return Some(directory.into());
+    }
+    #[cfg(windows)]
+    if let Some(directory) = std::env::var_os("LOCALAPPDATA") {
+        return Some(directory.into());
+    }
+    std::env::var_os("HOME").map(|home| std::path::PathBuf::from(home).join(".cache"))
+}
+
+fn font_path() -> std::path::PathBuf {
+    // NOTE: The font is kept in a per-user directory rather than in the
+    // shared temporary directory, where another user could plant a file.
+    cache_directory()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(FONT_DIRECTORY_NAME)
+        .join(FONT_FILE_NAME)
+}
+
+/// Writes the bundled font to the cache directory (unless already there),
+/// since FFmpeg can only load fonts from files.
+pub fn write_font_file() -> std::io::Result<()> {
+    let path = font_path();
+    // NOTE: The whole file is compared, so that a truncated, outdated or
+    // replaced font gets written again.
+    let is_written = std::fs::read(&path)
+        .map(|content| content == crate::FONT_BYTES_BOLD)
+        .unwrap_or(false);
+    if is_written {
+        return Ok(());
+    }
+    log::debug!(target: LOG_TARGET, "Writing overlay font to {:?}", path);
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+    std::fs::write(&path, crate::FONT_BYTES_BOLD)
+}
+
+/// Escapes a filter option's value, first for the option itself and then
+/// for the filtergraph it belongs to.
+fn escape(value: &str) -> String {
+    let escape_chars = |value: &str, special: &[char]| {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            if special.contains(&c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    };
+    let value = escape_chars(value, &['\\', '\'', ':']);
+    escape_chars(&value, &['\\', '\'', '[', ']', ',', ';'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlay(position: Position, background: bool) -> Overlay {
+        Overlay {
+            text: "Hi".into(),
+            position,
+            font_size: 20,
+            color: Color::White,
+            background,
+        }
+    }
+
+    fn drawtext(x: &str, y: &str) -> String {
+        format!(
+            "drawtext=fontfile={}:text=Hi:expansion=none:fontsize=20:fontcolor=white:x={}:y={}",
+            escape(&font_path().to_string_lossy()),
+            x,
+            y
+        )
+    }
+
+    #[test]
+    fn escapes_for_the_option_then_the_filtergraph() {
+        assert_eq!(escape("plain text"), "plain text");
+        assert_eq!(escape(r"it's 50:50, a\b"), r"it\\\'s 50\\:50\, a\\\\b");
+        assert_eq!(escape("[a];b"), r"\[a\]\;b");
+        assert_eq!(
+            escape(r"C:\Windows\Fonts\Roboto-Bold.ttf"),
+            r"C\\:\\\\Windows\\\\Fonts\\\\Roboto-Bold.ttf"
+        );
+    }
+
+    #[test]
+    fn escapes_the_text_within_the_filter() {
+        let overlay = Overlay {
+            text: r"it's 50:50, a\b".into(),
+            ..overlay(Position::TopLeft, false)
+        };
+        assert!(
+            overlay
+                .filter()
+                .contains(r":text=it\\\'s 50\\:50\, a\\\\b:expansion=none:"),
+            "{}",
+            overlay.filter()
+        );
+    }
+
+    #[test]
+    fn corners_use_a_box() {
+        let boxed = ":box=1:boxcolor=black@0.5:boxborderw=5";
+        let cases = [
+            (Position::TopLeft, "10", "10"),
+            (Position::TopRight, "w-tw-10", "10"),
+            (Position::BottomLeft, "10", "h-th-10"),
+            (Position::BottomRight, "w-tw-10", "h-th-10"),
+        ];
+        for (position, x, y) in cases {
+            assert_eq!(
+                overlay(position, true).filter(),
+                drawtext(x, y) + boxed,
+                "{}",
+                position
+            );
+            assert_eq!(
+                overlay(position, false).filter(),
+                drawtext(x, y),
+                "{}",
+                position
+            );
+        }
+    }
+
+    #[test]
+    fn banners_draw_a_band_first() {
+        assert_eq!(
+            overlay(Position::TopBanner, true).filter(),
+            format!(
+                "drawbox=x=0:y=0:w=iw:h=40:color=black@0.5:t=fill,{}",
+                drawtext("(w-tw)/2", "(40-th)/2")
+            )
+        );
+        assert_eq!(
+            overlay(Position::BottomBanner, true).filter(),
+            format!(
+                "drawbox=x=0:y=ih-40:w=iw:h=40:color=black@0.5:t=fill,{}",
+                drawtext("(w-tw)/2", "h-40+(40-th)/2")
+            )
+        );
+        assert_eq!(
+            overlay(Position::BottomBanner, false).filter(),
+            drawtext("(w-tw)/2", "h-40+(40-th)/2")
+        );
+    }
+}
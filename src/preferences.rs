@@ -0,0 +1,363 @@
+use crate::converter;
+use crate::overlay;
+
+const LOG_TARGET: &'static str = "iced_gif_maker::preferences";
+
+const DIRECTORY_NAME: &'static str = "iced_gif_maker";
+const FILE_NAME: &'static str = "preferences.conf";
+
+/// The settings that are remembered between sessions. They are stored as
+/// `key=value` lines, in a file located under the user's config directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preferences {
+    pub size_mode: converter::SizeMode,
+    pub width: u16,
+    pub height: u16,
+    pub size_percent: u16,
+    pub scaling: converter::ScaleAlgorithm,
+    pub fps: u8,
+    pub frame_delay: u16,
+    pub max_colors: u16,
+    pub dither: converter::Dither,
+    pub bayer_scale: u8,
+    pub stats_mode: converter::StatsMode,
+    pub format: converter::Format,
+    /// In KiB.
+    pub target_size: Option<u32>,
+    pub plays: Option<u16>,
+    pub overlay_text: String,
+    pub overlay_position: overlay::Position,
+    pub overlay_font_size: u16,
+    pub overlay_color: overlay::Color,
+    pub overlay_background: bool,
+    pub speed: converter::Speed,
+    pub direction: converter::Direction,
+    pub open_directory: Option<std::path::PathBuf>,
+    pub save_directory: Option<std::path::PathBuf>,
+    /// The FFmpeg binary chosen in the settings, which takes precedence
+    /// over the environment variable and the system's PATH.
+    pub ffmpeg_path: Option<std::path::PathBuf>,
+    pub window_size: (u32, u32),
+    pub window_position: Option<(i32, i32)>,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        let palette = converter::Palette::default();
+        Self {
+            size_mode: Default::default(),
+            width: crate::DEFAULT_GIF_WIDTH,
+            height: crate::DEFAULT_GIF_HEIGHT,
+            size_percent: crate::DEFAULT_SIZE_PERCENT,
+            scaling: Default::default(),
+            fps: crate::DEFAULT_GIF_FPS,
+            frame_delay: crate::DEFAULT_FRAME_DELAY_MS,
+            max_colors: palette.max_colors,
+            dither: palette.dither,
+            bayer_scale: palette.bayer_scale,
+            stats_mode: palette.stats_mode,
+            format: Default::default(),
+            target_size: None,
+            plays: None,
+            overlay_text: String::new(),
+            overlay_position: Default::default(),
+            overlay_font_size: overlay::Overlay::DEFAULT_FONT_SIZE,
+            overlay_color: Default::default(),
+            overlay_background: true,
+            speed: Default::default(),
+            direction: Default::default(),
+            open_directory: None,
+            save_directory: None,
+            ffmpeg_path: None,
+            window_size: crate::DEFAULT_WINDOW_SIZE,
+            window_position: Some(crate::DEFAULT_WINDOW_POSITION),
+        }
+    }
+}
+
+impl Preferences {
+    /// Loads the preferences file. Missing or invalid entries (or a missing
+    /// file) fall back to their default values.
+    pub fn load() -> Self {
+        let Some(path) = file_path() else {
+            log::warn!(target: LOG_TARGET, "Could not determine the config directory, so using default preferences.");
+            return Default::default();
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                log::debug!(target: LOG_TARGET, "Loading preferences from {:?}", path);
+                Self::parse(&content)
+            }
+            Err(e) => {
+                log::debug!(target: LOG_TARGET, "Could not read preferences from {:?} ({}), so using defaults.", path, e);
+                Default::default()
+            }
+        }
+    }
+
+    /// Saves the preferences on the current thread, which is meant for when
+    /// the application is about to exit.
+    pub fn save(&self) {
+        let Some(path) = file_path() else {
+            log::warn!(target: LOG_TARGET, "Could not determine the config directory, so preferences will not be saved.");
+            return;
+        };
+
+        let result = path
+            .parent()
+            .map(std::fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| std::fs::write(&path, self.to_string()));
+        match result {
+            Ok(_) => log::debug!(target: LOG_TARGET, "Preferences saved to {:?}", path),
+            Err(e) => {
+                log::warn!(target: LOG_TARGET, "Failed to save preferences to {:?}: {}", path, e)
+            }
+        }
+    }
+
+    /// Like `save`, but without blocking, and returning why saving failed.
+    pub async fn save_async(self) -> Result<(), String> {
+        let path = file_path()
+            .ok_or("Could not determine the config directory, so preferences were not saved.")?;
+        if let Some(directory) = path.parent() {
+            tokio::fs::create_dir_all(directory)
+                .await
+                .map_err(|e| format!("Failed to save preferences to {:?}: {}", path, e))?;
+        }
+        tokio::fs::write(&path, self.to_string())
+            .await
+            .map_err(|e| format!("Failed to save preferences to {:?}: {}", path, e))?;
+        log::debug!(target: LOG_TARGET, "Preferences saved to {:?}", path);
+        Ok(())
+    }
+
+    fn parse(content: &str) -> Self {
+        let mut preferences = Self::default();
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let Some((key, raw_value)) = line.split_once('=') else {
+                log::warn!(target: LOG_TARGET, "Ignoring invalid preferences line: {:?}", line);
+                continue;
+            };
+            let (key, value) = (key.trim(), raw_value.trim());
+
+            let parsed = match key {
+                "size_mode" => parse_choice(&converter::SizeMode::ALL, value)
+                    .map(|v| preferences.size_mode = v),
+                "width" => value.parse().map(|v| preferences.width = v).ok(),
+                "height" => value.parse().map(|v| preferences.height = v).ok(),
+                "size_percent" => value.parse().map(|v| preferences.size_percent = v).ok(),
+                "scaling" => parse_choice(&converter::ScaleAlgorithm::ALL, value)
+                    .map(|v| preferences.scaling = v),
+                "fps" => value.parse().map(|v| preferences.fps = v).ok(),
+                "frame_delay" => value.parse().map(|v| preferences.frame_delay = v).ok(),
+                "max_colors" => value.parse().map(|v| preferences.max_colors = v).ok(),
+                "dither" => {
+                    parse_choice(&converter::Dither::ALL, value).map(|v| preferences.dither = v)
+                }
+                "bayer_scale" => value.parse().map(|v| preferences.bayer_scale = v).ok(),
+                "stats_mode" => parse_choice(&converter::StatsMode::ALL, value)
+                    .map(|v| preferences.stats_mode = v),
+                "format" => {
+                    parse_choice(&converter::Format::ALL, value).map(|v| preferences.format = v)
+                }
+                "target_size" => value
+                    .parse()
+                    .map(|v| preferences.target_size = Some(v))
+                    .ok(),
+                "plays" => value.parse().map(|v| preferences.plays = Some(v)).ok(),
+                // NOTE: The caption is kept as is, since its leading or
+                // trailing spaces may be intended.
+                "overlay_text" => {
+                    preferences.overlay_text = raw_value.into();
+                    Some(())
+                }
+                "overlay_position" => parse_choice(&overlay::Position::ALL, value)
+                    .map(|v| preferences.overlay_position = v),
+                "overlay_font_size" => value
+                    .parse()
+                    .map(|v| preferences.overlay_font_size = v)
+                    .ok(),
+                "overlay_color" => {
+                    parse_choice(&overlay::Color::ALL, value).map(|v| preferences.overlay_color = v)
+                }
+                "overlay_background" => value
+                    .parse()
+                    .map(|v| preferences.overlay_background = v)
+                    .ok(),
+                "speed" => {
+                    parse_choice(&converter::Speed::ALL, value).map(|v| preferences.speed = v)
+                }
+                "direction" => parse_choice(&converter::Direction::ALL, value)
+                    .map(|v| preferences.direction = v),
+                "open_directory" => {
+                    preferences.open_directory = Some(value.into());
+                    Some(())
+                }
+                "save_directory" => {
+                    preferences.save_directory = Some(value.into());
+                    Some(())
+                }
+                "ffmpeg_path" => {
+                    preferences.ffmpeg_path = Some(value.into());
+                    Some(())
+                }
+                "window_size" => parse_pair(value).map(|v| preferences.window_size = v),
+                "window_position" => {
+                    parse_pair(value).map(|v| preferences.window_position = Some(v))
+                }
+                _ => None,
+            };
+
+            if parsed.is_none() {
+                log::warn!(target: LOG_TARGET, "Ignoring invalid preference: {:?}", line);
+            }
+        }
+
+        preferences
+    }
+}
+
+impl std::fmt::Display for Preferences {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# Iced Animated GIF Maker preferences")?;
+        writeln!(f, "size_mode={}", self.size_mode)?;
+        writeln!(f, "width={}", self.width)?;
+        writeln!(f, "height={}", self.height)?;
+        writeln!(f, "size_percent={}", self.size_percent)?;
+        writeln!(f, "scaling={}", self.scaling)?;
+        writeln!(f, "fps={}", self.fps)?;
+        writeln!(f, "frame_delay={}", self.frame_delay)?;
+        writeln!(f, "max_colors={}", self.max_colors)?;
+        writeln!(f, "dither={}", self.dither)?;
+        writeln!(f, "bayer_scale={}", self.bayer_scale)?;
+        writeln!(f, "stats_mode={}", self.stats_mode)?;
+        writeln!(f, "format={}", self.format)?;
+        writeln!(f, "speed={}", self.speed)?;
+        writeln!(f, "direction={}", self.direction)?;
+        if let Some(target_size) = self.target_size {
+            writeln!(f, "target_size={}", target_size)?;
+        }
+        if let Some(plays) = self.plays {
+            writeln!(f, "plays={}", plays)?;
+        }
+        if !self.overlay_text.is_empty() {
+            writeln!(f, "overlay_text={}", self.overlay_text)?;
+        }
+        writeln!(f, "overlay_position={}", self.overlay_position)?;
+        writeln!(f, "overlay_font_size={}", self.overlay_font_size)?;
+        writeln!(f, "overlay_color={}", self.overlay_color)?;
+        writeln!(f, "overlay_background={}", self.overlay_background)?;
+        if let Some(directory) = self.open_directory.as_ref() {
+            writeln!(f, "open_directory={}", directory.to_string_lossy())?;
+        }
+        if let Some(directory) = self.save_directory.as_ref() {
+            writeln!(f, "save_directory={}", directory.to_string_lossy())?;
+        }
+        if let Some(path) = self.ffmpeg_path.as_ref() {
+            writeln!(f, "ffmpeg_path={}", path.to_string_lossy())?;
+        }
+        writeln!(
+            f,
+            "window_size={},{}",
+            self.window_size.0, self.window_size.1
+        )?;
+        if let Some((x, y)) = self.window_position {
+            writeln!(f, "window_position={},{}", x, y)?;
+        }
+        Ok(())
+    }
+}
+
+fn config_directory() -> Option<std::path::PathBuf> {
+    if let Some(directory) = std::env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return Some(directory.into());
+    }
+    #[cfg(windows)]
+    if let Some(directory) = std::env::var_os("APPDATA") {
+        return Some(directory.into());
+    }
+    std::env::var_os("HOME").map(|home| std::path::PathBuf::from(home).join(".config"))
+}
+
+fn file_path() -> Option<std::path::PathBuf> {
+    config_directory().map(|directory| directory.join(DIRECTORY_NAME).join(FILE_NAME))
+}
+
+/// Finds the choice whose displayed name matches `value`.
+fn parse_choice<T: std::fmt::Display + Copy>(all: &[T], value: &str) -> Option<T> {
+    all.iter()
+        .find(|choice| choice.to_string() == value)
+        .copied()
+}
+
+fn parse_pair<T: std::str::FromStr>(value: &str) -> Option<(T, T)> {
+    let (a, b) = value.split_once(',')?;
+    Some((a.trim().parse().ok()?, b.trim().parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_text() {
+        let preferences = Preferences {
+            size_mode: converter::SizeMode::Fit,
+            width: 320,
+            height: 240,
+            size_percent: 75,
+            scaling: converter::ScaleAlgorithm::Neighbor,
+            fps: 15,
+            frame_delay: 40,
+            max_colors: 64,
+            dither: converter::Dither::Bayer,
+            bayer_scale: 3,
+            stats_mode: converter::StatsMode::Diff,
+            format: converter::Format::WebP,
+            target_size: Some(2048),
+            plays: Some(2),
+            overlay_text: "  Hello = world ".into(),
+            overlay_position: overlay::Position::TopBanner,
+            overlay_font_size: 32,
+            overlay_color: overlay::Color::Yellow,
+            overlay_background: false,
+            speed: converter::Speed::ALL[0],
+            direction: converter::Direction::Boomerang,
+            open_directory: Some("/videos".into()),
+            save_directory: Some("/gifs".into()),
+            ffmpeg_path: Some("/opt/ffmpeg/bin/ffmpeg".into()),
+            window_size: (800, 600),
+            window_position: Some((-10, 20)),
+        };
+        assert_ne!(preferences, Preferences::default());
+        assert_eq!(Preferences::parse(&preferences.to_string()), preferences);
+    }
+
+    #[test]
+    fn invalid_entries_keep_their_defaults() {
+        let preferences = Preferences::parse(
+            "# comment\n\
+             width=wide\n\
+             fps = 12 \n\
+             format=avi\n\
+             window_size=800\n\
+             unknown=1\n\
+             no separator\n",
+        );
+        assert_eq!(
+            preferences,
+            Preferences {
+                fps: 12,
+                ..Default::default()
+            }
+        );
+        assert_eq!(Preferences::parse(""), Preferences::default());
+    }
+}
@@ -0,0 +1,558 @@
+use crate::converter;
+use image::AnimationDecoder;
+
+const LOG_TARGET: &'static str = "iced_gif_maker::preview";
+
+/// Frames with a shorter delay are displayed for `DEFAULT_FRAME_DELAY_MS`
+/// instead, like browsers do.
+const MIN_FRAME_DELAY_MS: u64 = 20;
+const DEFAULT_FRAME_DELAY_MS: u64 = 100;
+
+#[derive(Clone, Debug)]
+pub enum Error {
+    Cancelled,
+    Read(String),
+    Decode(String),
+    Encode(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Cancelled => write!(f, "The decoding was cancelled."),
+            Error::Read(e) => write!(f, "Failed to read the animated GIF: {}", e),
+            Error::Decode(e) => write!(f, "Failed to decode the animated GIF: {}", e),
+            Error::Encode(e) => write!(f, "Failed to encode the animated GIF: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub handle: iced::widget::image::Handle,
+    /// The delay stored in the GIF, which is kept as is when encoding.
+    pub delay: std::time::Duration,
+}
+
+impl Frame {
+    /// The delay the frame is actually displayed for, since browsers slow
+    /// down frames whose delay is too short.
+    pub fn display_delay(&self) -> std::time::Duration {
+        if self.delay < std::time::Duration::from_millis(MIN_FRAME_DELAY_MS) {
+            std::time::Duration::from_millis(DEFAULT_FRAME_DELAY_MS)
+        } else {
+            self.delay
+        }
+    }
+
+    /// Returns the frame's pixels, which are kept by its (RGBA) handle.
+    pub fn to_image(&self) -> Option<image::RgbaImage> {
+        match self.handle.data() {
+            iced::widget::image::Data::Rgba {
+                width,
+                height,
+                pixels,
+            } => image::RgbaImage::from_raw(*width, *height, pixels.to_vec()),
+            _ => None,
+        }
+    }
+}
+
+/// The decoded frames of an animated GIF.
+#[derive(Clone, Debug)]
+pub struct Frames {
+    frames: Vec<Frame>,
+    width: u32,
+    height: u32,
+}
+
+impl Frames {
+    /// Decodes the GIF on a separate thread. Setting `cancel` makes the
+    /// decoding stop before the next frame.
+    pub async fn decode(
+        data: Vec<u8>,
+        cancel: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    ) -> Result<Self, Error> {
+        Self::spawn(move || Self::decode_blocking(&data, &cancel)).await
+    }
+
+    /// Like `decode`, but reads the GIF from a file first (on the same
+    /// separate thread).
+    pub async fn decode_file(
+        path: std::path::PathBuf,
+        cancel: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    ) -> Result<Self, Error> {
+        Self::spawn(move || {
+            let data = std::fs::read(&path).map_err(|e| Error::Read(e.to_string()))?;
+            Self::decode_blocking(&data, &cancel)
+        })
+        .await
+    }
+
+    /// Encodes the frames as an animated GIF using FFmpeg (on a separate
+    /// thread), with the given palette settings and each frame's delay.
+    pub async fn encode(
+        self,
+        ffmpeg_path: String,
+        palette: converter::Palette,
+        looping: converter::Loop,
+    ) -> Result<Vec<u8>, Error> {
+        let (tx, rx) = iced::futures::channel::oneshot::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(self.encode_blocking(ffmpeg_path, palette, looping));
+        });
+        rx.await
+            .map_err(|_| Error::Encode("Encoding thread stopped unexpectedly.".into()))?
+    }
+
+    fn encode_blocking(
+        &self,
+        ffmpeg_path: String,
+        palette: converter::Palette,
+        looping: converter::Loop,
+    ) -> Result<Vec<u8>, Error> {
+        // NOTE: The frames are written as PNG files, which only exist for
+        // the duration of the encoding.
+        let directory = converter::temp_file_path("frames");
+        std::fs::create_dir(&directory).map_err(|e| Error::Encode(e.to_string()))?;
+        let result = self.encode_in(&directory, ffmpeg_path, palette, looping);
+        if let Err(e) = std::fs::remove_dir_all(&directory) {
+            log::debug!(target: LOG_TARGET, "Could not remove temporary directory {:?}: {:?}", directory, e);
+        }
+        let data = result?;
+        log::debug!(target: LOG_TARGET, "Encoded {} frame(s) into {} bytes.", self.frames.len(), data.len());
+        Ok(data)
+    }
+
+    fn encode_in(
+        &self,
+        directory: &std::path::Path,
+        ffmpeg_path: String,
+        palette: converter::Palette,
+        looping: converter::Loop,
+    ) -> Result<Vec<u8>, Error> {
+        let mut paths = vec![];
+        for (index, frame) in self.frames.iter().enumerate() {
+            let path = directory.join(format!("{:06}.png", index));
+            frame
+                .to_image()
+                .ok_or(Error::Encode("Missing frame pixels.".into()))?
+                .save_with_format(&path, image::ImageFormat::Png)
+                .map_err(|e| Error::Encode(e.to_string()))?;
+            paths.push(path);
+        }
+
+        let sequence = converter::ImageSequence {
+            paths,
+            frame_delay: std::time::Duration::from_millis(DEFAULT_FRAME_DELAY_MS),
+            frame_delays: Some(self.frames.iter().map(|frame| frame.delay).collect()),
+        };
+        // NOTE: The frames were quantized using the same palette settings,
+        // so encoding them again keeps their colors. The frame rate goes
+        // unused, since each image keeps its own delay.
+        let settings = converter::Settings::new(
+            converter::Input::Images(sequence),
+            self.width() as u16,
+            crate::DEFAULT_GIF_FPS,
+        )
+        .palette(palette)
+        .format(converter::Format::Gif)
+        .looping(looping)
+        .ffmpeg_path(ffmpeg_path);
+        converter::Converter::convert_blocking(&settings).map_err(|e| Error::Encode(e.to_string()))
+    }
+
+    async fn spawn(
+        f: impl FnOnce() -> Result<Self, Error> + Send + 'static,
+    ) -> Result<Self, Error> {
+        let (tx, rx) = iced::futures::channel::oneshot::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(f());
+        });
+        rx.await
+            .map_err(|_| Error::Decode("Decoding thread stopped unexpectedly.".into()))?
+    }
+
+    fn decode_blocking(data: &[u8], cancel: &std::sync::atomic::AtomicBool) -> Result<Self, Error> {
+        let decoder = image::codecs::gif::GifDecoder::new(std::io::Cursor::new(data))
+            .map_err(|e| Error::Decode(e.to_string()))?;
+        let (width, height) = image::ImageDecoder::dimensions(&decoder);
+
+        let mut frames = vec![];
+        for frame in decoder.into_frames() {
+            if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                log::debug!(target: LOG_TARGET, "Decoding cancelled after {} frame(s).", frames.len());
+                return Err(Error::Cancelled);
+            }
+            let frame = frame.map_err(|e| Error::Decode(e.to_string()))?;
+            let delay = std::time::Duration::from(frame.delay());
+            let buffer = frame.into_buffer();
+            frames.push(Frame {
+                handle: iced::widget::image::Handle::from_pixels(
+                    buffer.width(),
+                    buffer.height(),
+                    buffer.into_raw(),
+                ),
+                delay,
+            });
+        }
+
+        if frames.is_empty() {
+            return Err(Error::Decode("The GIF has no frames.".into()));
+        }
+
+        log::debug!(target: LOG_TARGET, "Decoded {} frame(s).", frames.len());
+        Ok(Self {
+            frames,
+            width,
+            height,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get(&self, index: usize) -> &Frame {
+        &self.frames[index.min(self.frames.len() - 1)]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Frame> {
+        self.frames.iter()
+    }
+
+    /// The time at which the frame gets displayed.
+    pub fn timestamp(&self, index: usize) -> std::time::Duration {
+        self.frames
+            .iter()
+            .take(index)
+            .map(Frame::display_delay)
+            .sum()
+    }
+
+    pub fn duration(&self) -> std::time::Duration {
+        self.timestamp(self.frames.len())
+    }
+
+    pub fn average_delay(&self) -> std::time::Duration {
+        self.duration() / self.frames.len() as u32
+    }
+}
+
+/// Plays decoded frames, one `tick` at a time, as many times as the GIF's
+/// loop setting asks for.
+#[derive(Clone, Debug)]
+pub struct Player {
+    frames: Frames,
+    looping: converter::Loop,
+    index: usize,
+    playing: bool,
+    /// The number of plays completed since the playback was (re)started.
+    plays: u16,
+}
+
+impl Player {
+    pub fn new(frames: Frames, looping: converter::Loop) -> Self {
+        Self {
+            frames,
+            looping,
+            index: 0,
+            playing: true,
+            plays: 0,
+        }
+    }
+
+    pub fn frames(&self) -> &Frames {
+        &self.frames
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    fn is_last(&self) -> bool {
+        self.index + 1 == self.frames.len()
+    }
+
+    pub fn current(&self) -> &Frame {
+        self.frames.get(self.index)
+    }
+
+    /// Pauses or resumes the playback. Resuming restarts the play count,
+    /// and rewinds if the playback had stopped on the last frame.
+    pub fn toggle(&mut self) {
+        if !self.playing {
+            if self.is_last() {
+                self.index = 0;
+            }
+            self.plays = 0;
+        }
+        self.playing = !self.playing;
+    }
+
+    /// Moves by `offset` frames, wrapping around, and pauses the playback.
+    pub fn step(&mut self, offset: isize) {
+        let len = self.frames.len() as isize;
+        self.index = (self.index as isize + offset).rem_euclid(len) as usize;
+        self.playing = false;
+    }
+
+    /// Jumps to the frame and pauses the playback.
+    pub fn seek(&mut self, index: usize) {
+        self.index = index.min(self.frames.len() - 1);
+        self.playing = false;
+    }
+
+    /// Removes the current frame, unless it is the only one left.
+    pub fn remove_current(&mut self) {
+        if self.frames.len() > 1 {
+            self.frames.frames.remove(self.index);
+            self.index = self.index.min(self.frames.len() - 1);
+        }
+        self.playing = false;
+    }
+
+    /// Inserts a copy of the current frame after it, and moves to the copy.
+    pub fn duplicate_current(&mut self) {
+        let frame = self.current().clone();
+        self.index += 1;
+        self.frames.frames.insert(self.index, frame);
+        self.playing = false;
+    }
+
+    /// Changes how long the current frame is displayed for.
+    pub fn set_current_delay(&mut self, delay: std::time::Duration) {
+        self.frames.frames[self.index].delay = delay;
+        self.playing = false;
+    }
+
+    pub fn tick(&mut self) {
+        if !self.playing {
+            return;
+        }
+        if !self.is_last() {
+            self.index += 1;
+            return;
+        }
+
+        self.plays = self.plays.saturating_add(1);
+        match self.looping {
+            // NOTE: Like browsers do, the playback stops on the last frame.
+            converter::Loop::Times(plays) if self.plays >= plays => {
+                log::debug!(target: LOG_TARGET, "Playback stopped after {} play(s).", self.plays);
+                self.playing = false;
+            }
+            _ => self.index = 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(millis: u64) -> std::time::Duration {
+        std::time::Duration::from_millis(millis)
+    }
+
+    /// Builds 1x1 frames with the given delays (in milliseconds), which
+    /// also tell the frames apart.
+    fn frames(delays: &[u64]) -> Frames {
+        Frames {
+            frames: delays
+                .iter()
+                .map(|&delay| Frame {
+                    handle: iced::widget::image::Handle::from_pixels(1, 1, vec![0; 4]),
+                    delay: millis(delay),
+                })
+                .collect(),
+            width: 1,
+            height: 1,
+        }
+    }
+
+    fn delays(player: &Player) -> Vec<u64> {
+        player
+            .frames()
+            .iter()
+            .map(|frame| frame.delay.as_millis() as u64)
+            .collect()
+    }
+
+    #[test]
+    fn steps_wrap_around() {
+        let mut player = Player::new(frames(&[100, 200, 300]), converter::Loop::Forever);
+        assert!(player.is_playing());
+        player.step(-1);
+        assert_eq!(player.index(), 2);
+        assert!(!player.is_playing());
+        player.step(1);
+        assert_eq!(player.index(), 0);
+        player.step(-4);
+        assert_eq!(player.index(), 2);
+    }
+
+    #[test]
+    fn seeking_past_the_end_stops_on_the_last_frame() {
+        let mut player = Player::new(frames(&[100, 200, 300]), converter::Loop::Forever);
+        player.seek(1);
+        assert_eq!(player.index(), 1);
+        assert!(!player.is_playing());
+        player.seek(10);
+        assert_eq!(player.index(), 2);
+        assert_eq!(player.current().delay, millis(300));
+    }
+
+    #[test]
+    fn toggling_pauses_and_resumes() {
+        let mut player = Player::new(frames(&[100, 200, 300]), converter::Loop::Forever);
+        player.toggle();
+        assert!(!player.is_playing());
+        player.tick();
+        assert_eq!(player.index(), 0);
+        player.toggle();
+        player.tick();
+        assert_eq!(player.index(), 1);
+
+        // NOTE: Resuming from the last frame rewinds.
+        player.seek(2);
+        player.toggle();
+        assert!(player.is_playing());
+        assert_eq!(player.index(), 0);
+    }
+
+    fn play(player: &mut Player, ticks: usize) {
+        for _ in 0..ticks {
+            player.tick();
+        }
+    }
+
+    #[test]
+    fn playing_once_stops_on_the_last_frame() {
+        let mut player = Player::new(frames(&[100, 200, 300]), converter::Loop::Times(1));
+        play(&mut player, 2);
+        assert_eq!(player.index(), 2);
+        assert!(player.is_playing());
+        player.tick();
+        assert_eq!(player.index(), 2);
+        assert!(!player.is_playing());
+        play(&mut player, 5);
+        assert_eq!(player.index(), 2);
+
+        // NOTE: Playing again restarts from the first frame, for another
+        // full play.
+        player.toggle();
+        assert_eq!(player.index(), 0);
+        assert!(player.is_playing());
+        play(&mut player, 2);
+        assert!(player.is_playing());
+        player.tick();
+        assert_eq!(player.index(), 2);
+        assert!(!player.is_playing());
+    }
+
+    #[test]
+    fn playing_several_times_wraps_between_plays() {
+        let mut player = Player::new(frames(&[100, 200, 300]), converter::Loop::Times(3));
+        // NOTE: Each play shows 3 frames, and the last one stops instead of
+        // wrapping, so 3 plays wrap twice.
+        play(&mut player, 3);
+        assert_eq!(player.index(), 0);
+        play(&mut player, 3);
+        assert_eq!(player.index(), 0);
+        assert!(player.is_playing());
+        play(&mut player, 3);
+        assert_eq!(player.index(), 2);
+        assert!(!player.is_playing());
+    }
+
+    #[test]
+    fn looping_forever_never_stops() {
+        let mut player = Player::new(frames(&[100, 200, 300]), converter::Loop::Forever);
+        play(&mut player, 3 * 1000 + 1);
+        assert!(player.is_playing());
+        assert_eq!(player.index(), 1);
+    }
+
+    #[test]
+    fn timestamps_add_up_the_delays() {
+        let frames = frames(&[100, 50, 30]);
+        assert_eq!(frames.timestamp(0), millis(0));
+        assert_eq!(frames.timestamp(1), millis(100));
+        assert_eq!(frames.timestamp(2), millis(150));
+        assert_eq!(frames.duration(), millis(180));
+        assert_eq!(frames.average_delay(), millis(60));
+        assert_eq!(frames.get(5).delay, millis(30));
+    }
+
+    #[test]
+    fn removing_keeps_at_least_one_frame() {
+        let mut player = Player::new(frames(&[100, 200, 300]), converter::Loop::Forever);
+        player.seek(2);
+        player.remove_current();
+        assert_eq!(delays(&player), [100, 200]);
+        assert_eq!(player.index(), 1);
+        player.seek(0);
+        player.remove_current();
+        assert_eq!(delays(&player), [200]);
+        assert_eq!(player.index(), 0);
+        player.remove_current();
+        assert_eq!(delays(&player), [200]);
+        assert_eq!(player.current().delay, millis(200));
+    }
+
+    #[test]
+    fn duplicating_inserts_after_the_current_frame() {
+        let mut player = Player::new(frames(&[100, 200, 300]), converter::Loop::Forever);
+        player.seek(1);
+        player.duplicate_current();
+        assert_eq!(delays(&player), [100, 200, 200, 300]);
+        assert_eq!(player.index(), 2);
+        player.seek(3);
+        player.duplicate_current();
+        assert_eq!(delays(&player), [100, 200, 200, 300, 300]);
+        assert_eq!(player.index(), 4);
+    }
+
+    #[test]
+    fn retiming_changes_the_current_frame_only() {
+        let mut player = Player::new(frames(&[100, 200, 300]), converter::Loop::Forever);
+        player.seek(1);
+        player.set_current_delay(millis(40));
+        assert_eq!(delays(&player), [100, 40, 300]);
+        assert!(!player.is_playing());
+    }
+
+    #[test]
+    fn short_delays_are_displayed_like_browsers_do() {
+        let frames = frames(&[0, 10, 19, 20, 50]);
+        let display_delays: Vec<_> = frames.iter().map(Frame::display_delay).collect();
+        assert_eq!(
+            display_delays,
+            [
+                millis(100),
+                millis(100),
+                millis(100),
+                millis(20),
+                millis(50)
+            ]
+        );
+        // NOTE: The stored delays are kept, since they get encoded as is.
+        assert_eq!(frames.get(0).delay, millis(0));
+        assert_eq!(frames.duration(), millis(370));
+    }
+}
@@ -100,6 +100,7 @@ pub enum CustomContainerStyle {
     Sidebar,
     Preview,
     Footer,
+    Error,
 }
 
 pub struct CustomContainer(CustomContainerStyle);
@@ -127,6 +128,10 @@ impl CustomContainer {
         Self(CustomContainerStyle::Footer)
     }
 
+    pub fn error() -> Self {
+        Self(CustomContainerStyle::Error)
+    }
+
     pub fn move_to_style(self) -> iced::theme::Container {
         self.into()
     }
@@ -145,6 +150,7 @@ impl iced::widget::container::StyleSheet for CustomContainer {
         let background_color = match self.0 {
             CustomContainerStyle::Default => style.palette().background,
             CustomContainerStyle::Footer => iced::Color::BLACK,
+            CustomContainerStyle::Error => style.extended_palette().danger.weak.color,
             CustomContainerStyle::Preview => {
                 let mut c = style.palette().background;
                 c.r += 0.1;
@@ -168,7 +174,13 @@ impl iced::widget::container::StyleSheet for CustomContainer {
             }
         };
 
+        let text_color = match self.0 {
+            CustomContainerStyle::Error => Some(style.extended_palette().danger.weak.text),
+            _ => None,
+        };
+
         iced::widget::container::Appearance {
+            text_color,
             background: Some(background_color.into()),
             border_radius: 0.0.into(),
             border_color: iced::Color::TRANSPARENT,
@@ -0,0 +1,315 @@
+use crate::converter;
+
+const LOG_TARGET: &'static str = "iced_gif_maker::target_size";
+
+/// The successive reductions applied to the user's settings, from the
+/// highest to the lowest quality: a size factor, an FPS factor and a cap on
+/// the palette's color count.
+const STEPS: [(f32, f32, u16); 10] = [
+    (1.0, 1.0, 256),
+    (1.0, 1.0, 128),
+    (0.85, 1.0, 128),
+    (0.85, 0.8, 128),
+    (0.7, 0.8, 64),
+    (0.6, 0.7, 64),
+    (0.5, 0.6, 32),
+    (0.4, 0.5, 32),
+    (0.3, 0.5, 16),
+    (0.25, 0.4, 16),
+];
+
+/// The smallest dimension (or percentage) the size factor goes down to.
+const MIN_DIMENSION: u16 = 32;
+const MIN_FPS: u8 = 1;
+/// The target size is entered (and displayed) in KiB.
+const BYTES_PER_KIB: usize = 1024;
+
+/// Converts a target size in KiB into a budget in bytes. An empty (or zero)
+/// target size disables the mode.
+pub fn budget_from_kib(kib: Option<u32>) -> Option<usize> {
+    kib.filter(|&kib| kib > 0)
+        .map(|kib| kib as usize * BYTES_PER_KIB)
+}
+
+/// Formats a budget in KiB, like the target size was entered.
+pub fn format_budget(budget: usize) -> String {
+    format!("{} KiB", budget / BYTES_PER_KIB)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attempt {
+    pub dimensions: converter::Size,
+    pub fps: u8,
+    pub max_colors: u16,
+    /// The size of the produced file, once known.
+    pub size: Option<usize>,
+}
+
+impl std::fmt::Display for Attempt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {} FPS, {} colors",
+            self.dimensions, self.fps, self.max_colors
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    /// The last attempt fits within the budget, and this is its output.
+    Fits(Vec<u8>),
+    /// The last attempt was too big, so the conversion must be run again
+    /// with these settings.
+    Retry(converter::Settings),
+    /// Every step was attempted without fitting within the budget, and this
+    /// is the smallest output (the earliest one, in case of a tie).
+    Exhausted(Vec<u8>),
+}
+
+/// Searches for the highest-quality settings whose output fits within a
+/// byte budget, by converting again with lower settings until it does.
+#[derive(Clone, Debug)]
+pub struct Search {
+    budget: usize,
+    settings: converter::Settings,
+    dimensions: converter::Size,
+    fps: u8,
+    palette: converter::Palette,
+    step: usize,
+    attempts: Vec<Attempt>,
+    /// The smallest output so far, kept in case no attempt fits.
+    smallest_output: Option<Vec<u8>>,
+}
+
+impl Search {
+    pub fn new(
+        budget: usize,
+        settings: converter::Settings,
+        dimensions: converter::Size,
+        fps: u8,
+        palette: converter::Palette,
+    ) -> Self {
+        Self {
+            budget,
+            settings,
+            dimensions,
+            fps,
+            palette,
+            step: 0,
+            attempts: vec![],
+            smallest_output: None,
+        }
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    /// Returns the settings for the first attempt.
+    pub fn start(&mut self) -> converter::Settings {
+        self.step = 0;
+        self.attempts.clear();
+        self.smallest_output = None;
+        self.attempt()
+    }
+
+    /// Records the output produced by the last attempt and decides what to
+    /// do next.
+    pub fn record(&mut self, output: Vec<u8>) -> Outcome {
+        let size = output.len();
+        if let Some(attempt) = self.attempts.last_mut() {
+            attempt.size = Some(size);
+        }
+
+        if size <= self.budget {
+            log::debug!(target: LOG_TARGET, "Attempt {} fits within the budget ({} <= {}).", self.attempts.len(), size, self.budget);
+            self.smallest_output = None;
+            return Outcome::Fits(output);
+        }
+
+        if self
+            .smallest_output
+            .as_ref()
+            .map_or(true, |smallest| size < smallest.len())
+        {
+            self.smallest_output = Some(output);
+        }
+
+        // NOTE: Steps that would produce the same settings as the previous
+        // attempt (e.g. when the user already asked for few colors) are skipped.
+        let previous = self.attempts.last().copied();
+        while self.step + 1 < STEPS.len() {
+            self.step += 1;
+            if previous.map(|p| Attempt { size: None, ..p }) != Some(self.parameters()) {
+                log::debug!(target: LOG_TARGET, "Attempt {} is over budget ({} > {}), so trying again.", self.attempts.len(), size, self.budget);
+                return Outcome::Retry(self.attempt());
+            }
+        }
+
+        log::debug!(target: LOG_TARGET, "No attempt fits within the budget of {} bytes.", self.budget);
+        Outcome::Exhausted(self.smallest_output.take().unwrap_or_default())
+    }
+
+    /// The parameters of the current step. Those that do not affect the
+    /// output (e.g. colors for WebP, or the FPS for image sequences) are kept
+    /// as is, so that the steps only changing them get skipped.
+    fn parameters(&self) -> Attempt {
+        let (size_factor, fps_factor, max_colors) = STEPS[self.step];
+        Attempt {
+            dimensions: self.dimensions.scaled(size_factor, MIN_DIMENSION),
+            fps: if self.settings.uses_fps() {
+                ((self.fps as f32 * fps_factor).round() as u8).max(MIN_FPS)
+            } else {
+                self.fps
+            },
+            max_colors: if self.settings.uses_palette() {
+                self.palette.max_colors.min(max_colors)
+            } else {
+                self.palette.max_colors
+            },
+            size: None,
+        }
+    }
+
+    fn attempt(&mut self) -> converter::Settings {
+        let attempt = self.parameters();
+        self.attempts.push(attempt);
+        self.settings
+            .clone()
+            .size(attempt.dimensions)
+            .fps(attempt.fps)
+            .palette(converter::Palette {
+                max_colors: attempt.max_colors,
+                ..self.palette
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUDGET: usize = 1000;
+
+    fn search(settings: converter::Settings) -> Search {
+        let mut search = Search::new(
+            BUDGET,
+            settings,
+            converter::Size::Width(400),
+            20,
+            Default::default(),
+        );
+        search.start();
+        search
+    }
+
+    fn video() -> converter::Settings {
+        converter::Settings::new(converter::Input::Video("input.mp4".into()), 400, 20)
+    }
+
+    fn output(size: usize) -> Vec<u8> {
+        vec![0; size]
+    }
+
+    #[test]
+    fn budget_is_entered_in_kib() {
+        assert_eq!(budget_from_kib(Some(2)), Some(2048));
+        assert_eq!(budget_from_kib(Some(0)), None);
+        assert_eq!(budget_from_kib(None), None);
+        assert_eq!(format_budget(2048), "2 KiB");
+
+        let budget = budget_from_kib(Some(1)).unwrap();
+        let mut search = Search::new(
+            budget,
+            video(),
+            converter::Size::Width(400),
+            20,
+            Default::default(),
+        );
+        search.start();
+        assert!(matches!(search.record(output(1025)), Outcome::Retry(_)));
+        assert!(matches!(search.record(output(1024)), Outcome::Fits(_)));
+        assert_eq!(search.budget(), 1024);
+    }
+
+    #[test]
+    fn fits_on_first_attempt() {
+        let mut search = search(video());
+        match search.record(output(BUDGET)) {
+            Outcome::Fits(data) => assert_eq!(data.len(), BUDGET),
+            outcome => panic!("unexpected outcome: {:?}", outcome),
+        }
+        assert_eq!(search.attempts().len(), 1);
+        assert_eq!(search.attempts()[0].size, Some(BUDGET));
+    }
+
+    #[test]
+    fn retries_with_lower_settings() {
+        let mut search = search(video());
+        assert!(matches!(search.record(output(2000)), Outcome::Retry(_)));
+        assert!(matches!(search.record(output(1500)), Outcome::Retry(_)));
+        let attempts = search.attempts();
+        assert_eq!(attempts.len(), 3);
+        assert_eq!(attempts[0].max_colors, 256);
+        assert_eq!(attempts[1].dimensions, converter::Size::Width(400));
+        assert_eq!(attempts[1].max_colors, 128);
+        assert_eq!(attempts[2].dimensions, converter::Size::Width(340));
+        assert_eq!(attempts[2].size, None);
+        assert!(matches!(search.record(output(900)), Outcome::Fits(_)));
+    }
+
+    #[test]
+    fn keeps_the_smallest_output_when_exhausted() {
+        let mut search = search(video());
+        // NOTE: Lower settings do not always produce smaller outputs.
+        let mut sizes = [1500, 1400, 1200, 1800]
+            .into_iter()
+            .chain(std::iter::repeat(1300));
+        let mut outcome = search.record(output(sizes.next().unwrap()));
+        while let Outcome::Retry(_) = outcome {
+            outcome = search.record(output(sizes.next().unwrap()));
+        }
+        match outcome {
+            Outcome::Exhausted(data) => assert_eq!(data.len(), 1200),
+            outcome => panic!("unexpected outcome: {:?}", outcome),
+        }
+        assert_eq!(search.attempts().len(), STEPS.len());
+    }
+
+    #[test]
+    fn skips_color_steps_without_a_palette() {
+        let mut search = search(video().format(converter::Format::WebP));
+        while let Outcome::Retry(_) = search.record(output(2000)) {}
+        let attempts = search.attempts();
+        assert!(attempts.iter().all(|attempt| attempt.max_colors == 256));
+        assert_eq!(attempts[1].dimensions, converter::Size::Width(340));
+        assert!(attempts
+            .windows(2)
+            .all(|pair| pair[0].dimensions != pair[1].dimensions || pair[0].fps != pair[1].fps));
+    }
+
+    #[test]
+    fn skips_fps_steps_for_image_sequences() {
+        let images = converter::Input::Images(converter::ImageSequence {
+            paths: vec!["1.png".into(), "2.png".into()],
+            frame_delay: std::time::Duration::from_millis(100),
+            frame_delays: None,
+        });
+        let mut search = search(converter::Settings::new(images, 400, 20));
+        while let Outcome::Retry(_) = search.record(output(2000)) {}
+        let attempts = search.attempts();
+        assert!(attempts.iter().all(|attempt| attempt.fps == 20));
+        assert!(attempts.windows(2).all(|pair| Attempt {
+            size: None,
+            ..pair[0]
+        } != Attempt {
+            size: None,
+            ..pair[1]
+        }));
+    }
+}
@@ -1,21 +1,23 @@
-use ffmpeg_gif_maker::{Command, CommandSender, Converter, Message, MessageReceiver, Settings};
+use crate::converter::{Command, CommandSender, Converter, Message, MessageReceiver, Settings};
 use iced::futures::channel::mpsc;
 use iced::futures::sink::SinkExt;
 
 const LOG_TARGET: &'static str = "iced_gif_maker::worker";
 
+pub type JobId = u64;
+
 #[derive(Clone, Debug)]
 pub enum Event {
     Ready(mpsc::Sender<Input>),
-    Message(Message),
-    CommandRefused(Command),
-    TaskRefused(Settings),
+    Started(JobId),
+    Message(JobId, Message),
+    CommandRefused(JobId, Command),
 }
 
 #[derive(Debug)]
 pub enum Input {
-    Command(Command),
-    NewTask(Settings),
+    Command(JobId, Command),
+    NewTask(JobId, Settings),
 }
 
 pub enum State {
@@ -25,6 +27,39 @@ pub enum State {
 
 const CHANNEL_SIZE: usize = 100;
 
+async fn send_event(output: &mut mpsc::Sender<Event>, event: Event) {
+    if let Err(e) = output.send(event).await {
+        log::error!(target: LOG_TARGET, "Failed to send event to app: {:?}", e);
+        panic!();
+    }
+}
+
+/// Handles a command for a job that is not the one currently being converted.
+/// If the job is still queued, it gets removed from the queue and reported
+/// as cancelled; otherwise the command is refused.
+async fn handle_queued_job_command(
+    output: &mut mpsc::Sender<Event>,
+    queue: &mut std::collections::VecDeque<(JobId, Settings)>,
+    job_id: JobId,
+    command: Command,
+) {
+    let Some(index) = queue.iter().position(|(id, _)| *id == job_id) else {
+        log::warn!(target: LOG_TARGET, "Command refused because job {} does not exist: {:?}", job_id, command);
+        send_event(output, Event::CommandRefused(job_id, command)).await;
+        return;
+    };
+
+    match command {
+        Command::Cancel => {
+            log::debug!(target: LOG_TARGET, "Removing job {} from the queue...", job_id);
+            queue.remove(index);
+            let cancelled = Message::Error(crate::converter::Error::Cancelled);
+            send_event(output, Event::Message(job_id, cancelled)).await;
+            send_event(output, Event::Message(job_id, Message::Done)).await;
+        }
+    }
+}
+
 pub fn worker() -> iced::Subscription<Event> {
     struct Worker;
 
@@ -33,8 +68,8 @@ pub fn worker() -> iced::Subscription<Event> {
         CHANNEL_SIZE,
         |mut my_output| async move {
             let mut state = State::Starting;
-            let mut rx: Option<MessageReceiver> = None;
-            let mut tx: Option<CommandSender> = None;
+            let mut current: Option<(JobId, MessageReceiver, CommandSender)> = None;
+            let mut queue: std::collections::VecDeque<(JobId, Settings)> = Default::default();
 
             log::debug!(target: LOG_TARGET, "Entering main loop...");
 
@@ -45,10 +80,7 @@ pub fn worker() -> iced::Subscription<Event> {
                         log::debug!(target: LOG_TARGET, "Entered state's STARTING branch. Creating channel...");
 
                         let (sender, receiver) = mpsc::channel(CHANNEL_SIZE);
-                        if let Err(e) = my_output.send(Event::Ready(sender)).await {
-                            log::error!(target: LOG_TARGET, "Failed to send message to app {:?}", e);
-                            panic!();
-                        }
+                        send_event(&mut my_output, Event::Ready(sender)).await;
                         log::debug!(target: LOG_TARGET, "Channel created and sender part sent to app.");
                         state = State::Ready(receiver);
                     }
@@ -57,24 +89,25 @@ pub fn worker() -> iced::Subscription<Event> {
 
                         use iced::futures::StreamExt;
 
-                        if let (Some(message_rx), Some(command_tx)) = (rx.as_mut(), tx.as_ref()) {
-                            log::debug!(target: LOG_TARGET, "Converter channels present, so entering job loop...");
+                        if let Some((job_id, message_rx, command_tx)) = current.as_mut() {
+                            log::debug!(target: LOG_TARGET, "Converter channels present for job {}, so entering job loop...", job_id);
 
                             loop {
                                 tokio::select! {
                                     input = receiver.select_next_some() => {
                                         match input {
-                                            Input::Command(command) => {
+                                            Input::Command(id, command) if id == *job_id => {
                                                 log::debug!(target: LOG_TARGET, "Received command from application. Transfering it to FFmpeg converter...");
                                                 if let Err(e) = command_tx.send(command) {
                                                     log::warn!(target: LOG_TARGET, "Failed to send command to converter: {:?}", e);
                                                 }
                                             }
-                                            Input::NewTask(settings) => {
-                                                if let Err(e) = my_output.send(Event::TaskRefused(settings)).await {
-                                                    log::error!(target: LOG_TARGET, "Failed to send event: {:?}", e);
-                                                    panic!();
-                                                }
+                                            Input::Command(id, command) => {
+                                                handle_queued_job_command(&mut my_output, &mut queue, id, command).await;
+                                            }
+                                            Input::NewTask(id, settings) => {
+                                                log::debug!(target: LOG_TARGET, "Converter busy, so queueing job {}...", id);
+                                                queue.push_back((id, settings));
                                             }
                                         }
                                     },
@@ -83,10 +116,7 @@ pub fn worker() -> iced::Subscription<Event> {
                                             log::debug!(target: LOG_TARGET, "Received command message from converter (see 'trace' for details)");
                                             log::trace!(target: LOG_TARGET, "Mesage\n{:?}", message);
                                             let should_break = if let Message::Done = &message { true } else { false };
-                                            if let Err(e) = my_output.send(Event::Message(message)).await {
-                                                log::error!(target: LOG_TARGET, "Failed to send event message: {:?}", e);
-                                                panic!();
-                                            }
+                                            send_event(&mut my_output, Event::Message(*job_id, message)).await;
                                             if should_break {
                                                 log::debug!(target: LOG_TARGET, "Converter sent DONE message, so breaking out of loop...");
                                                 break;
@@ -100,30 +130,9 @@ pub fn worker() -> iced::Subscription<Event> {
                                 };
                             }
                             log::debug!(target: LOG_TARGET, "Releasing the converter channels...");
-                            rx = None;
-                            tx = None;
-                        } else {
-                            log::debug!(target: LOG_TARGET, "Converter channels not present, so waiting for input from application...");
-
-                            let input = receiver.select_next_some().await;
-
-                            log::debug!(target: LOG_TARGET, "Input received from application: {:?}", input);
-
-                            let settings = match input {
-                                Input::NewTask(settings) => settings,
-                                Input::Command(command) => {
-                                    log::warn!(target: LOG_TARGET, "Command refused because no conversion job exists: {:?}", command);
-                                    if let Err(e) =
-                                        my_output.send(Event::CommandRefused(command)).await
-                                    {
-                                        log::error!(target: LOG_TARGET, "Failed to send event to application: {:?}", e);
-                                        panic!();
-                                    }
-                                    continue;
-                                }
-                            };
-
-                            log::debug!(target: LOG_TARGET, "Instantiating converter and associated channels...");
+                            current = None;
+                        } else if let Some((job_id, settings)) = queue.pop_front() {
+                            log::debug!(target: LOG_TARGET, "Instantiating converter and associated channels for job {}...", job_id);
                             let (converter, sender, receiver) = Converter::new_with_channels();
 
                             log::debug!(target: LOG_TARGET, "Spawning thread for conversion job...");
@@ -133,8 +142,27 @@ pub fn worker() -> iced::Subscription<Event> {
                             });
 
                             log::debug!(target: LOG_TARGET, "Storing converter channels...");
-                            rx = Some(receiver);
-                            tx = Some(sender);
+                            current = Some((job_id, receiver, sender));
+                            send_event(&mut my_output, Event::Started(job_id)).await;
+                        } else {
+                            log::debug!(target: LOG_TARGET, "Converter channels not present, so waiting for input from application...");
+
+                            let input = receiver.select_next_some().await;
+
+                            log::debug!(target: LOG_TARGET, "Input received from application: {:?}", input);
+
+                            match input {
+                                Input::NewTask(id, settings) => queue.push_back((id, settings)),
+                                Input::Command(id, command) => {
+                                    handle_queued_job_command(
+                                        &mut my_output,
+                                        &mut queue,
+                                        id,
+                                        command,
+                                    )
+                                    .await;
+                                }
+                            }
                         }
                     }
                 }